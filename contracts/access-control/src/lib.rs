@@ -0,0 +1,92 @@
+//! Role-based access control shared by the workspace contracts.
+//!
+//! Roles are any contract type convertible to a `Val`, usually a `#[contracttype]`
+//! enum defined by the contract. This module only stores and queries grants; the
+//! contract decides who may grant or revoke roles and which role guards which
+//! entry point.
+#![no_std]
+use soroban_sdk::{symbol_short, Address, Env, IntoVal, Symbol, Val};
+
+const ROLE_KEY: Symbol = symbol_short!("ROLE");
+
+const DAY_IN_LEDGERS: u32 = 17280;
+const ROLE_BUMP_AMOUNT: u32 = 120 * DAY_IN_LEDGERS;
+const ROLE_LIFETIME_THRESHOLD: u32 = ROLE_BUMP_AMOUNT - 30 * DAY_IN_LEDGERS;
+
+fn role_key<R: IntoVal<Env, Val>>(
+    env: &Env,
+    role: &R,
+    account: &Address,
+) -> (Symbol, Val, Address) {
+    (ROLE_KEY, role.into_val(env), account.clone())
+}
+
+/// Returns true if `account` has been granted `role`
+pub fn has_role<R: IntoVal<Env, Val>>(env: &Env, role: &R, account: &Address) -> bool {
+    let key = role_key(env, role, account);
+    let granted = env.storage().persistent().has(&key);
+
+    if granted {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, ROLE_LIFETIME_THRESHOLD, ROLE_BUMP_AMOUNT);
+    }
+
+    granted
+}
+
+/// Grants `role` to `account` and emits a `role_granted` event. Returns false
+/// without emitting if the account already had the role.
+pub fn grant_role<R: IntoVal<Env, Val>>(
+    env: &Env,
+    role: &R,
+    account: &Address,
+    sender: &Address,
+) -> bool {
+    if has_role(env, role, account) {
+        return false;
+    }
+
+    let key = role_key(env, role, account);
+    env.storage().persistent().set(&key, &true);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ROLE_LIFETIME_THRESHOLD, ROLE_BUMP_AMOUNT);
+
+    let topics = (
+        Symbol::new(env, "role_granted"),
+        role.into_val(env),
+        account.clone(),
+    );
+    env.events().publish(topics, sender.clone());
+
+    true
+}
+
+/// Revokes `role` from `account` and emits a `role_revoked` event. Returns false
+/// without emitting if the account did not have the role.
+pub fn revoke_role<R: IntoVal<Env, Val>>(
+    env: &Env,
+    role: &R,
+    account: &Address,
+    sender: &Address,
+) -> bool {
+    let key = role_key(env, role, account);
+
+    if !env.storage().persistent().has(&key) {
+        return false;
+    }
+
+    env.storage().persistent().remove(&key);
+
+    let topics = (
+        Symbol::new(env, "role_revoked"),
+        role.into_val(env),
+        account.clone(),
+    );
+    env.events().publish(topics, sender.clone());
+
+    true
+}
+
+mod test;
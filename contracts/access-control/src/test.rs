@@ -0,0 +1,82 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{contract, contracttype, vec, IntoVal};
+
+#[contract]
+struct Contract;
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Role {
+    Minter,
+    Burner,
+}
+
+fn setup() -> (Env, Address) {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    (env, contract_id)
+}
+
+#[test]
+fn test_grant_and_revoke_role() {
+    let (env, contract_id) = setup();
+    let admin = Address::generate(&env);
+    let account = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        assert!(!has_role(&env, &Role::Minter, &account));
+
+        assert!(grant_role(&env, &Role::Minter, &account, &admin));
+        assert!(has_role(&env, &Role::Minter, &account));
+        assert!(!has_role(&env, &Role::Burner, &account));
+
+        assert!(revoke_role(&env, &Role::Minter, &account, &admin));
+        assert!(!has_role(&env, &Role::Minter, &account));
+    });
+}
+
+#[test]
+fn test_grant_role_emits_event() {
+    let (env, contract_id) = setup();
+    let admin = Address::generate(&env);
+    let account = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        grant_role(&env, &Role::Burner, &account, &admin);
+    });
+
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id,
+                (
+                    Symbol::new(&env, "role_granted"),
+                    Role::Burner,
+                    account.clone()
+                )
+                    .into_val(&env),
+                admin.into_val(&env),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_grant_and_revoke_are_idempotent() {
+    let (env, contract_id) = setup();
+    let admin = Address::generate(&env);
+    let account = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        assert!(!revoke_role(&env, &Role::Minter, &account, &admin));
+
+        assert!(grant_role(&env, &Role::Minter, &account, &admin));
+        assert!(!grant_role(&env, &Role::Minter, &account, &admin));
+        assert!(has_role(&env, &Role::Minter, &account));
+    });
+}
@@ -5,7 +5,8 @@
 //! Verifiable Credentials principles.
 
 use soroban_sdk::{
-    contract, contractimpl, panic_with_error, Address, Bytes, BytesN, Env, String, Vec,
+    contract, contractimpl, panic_with_error, vec, Address, Bytes, BytesN, Env, Map, String,
+    Symbol, Vec,
 };
 
 use crate::{errors::Error, events::Events, storage::Storage, types::*, utils::*};
@@ -18,17 +19,23 @@ impl CertificateNFT {
     pub fn __constructor(env: Env, name: String, symbol: String) {
         Storage::set_name(&env, &name);
         Storage::set_symbol(&env, &symbol);
+        Storage::set_schema_version(&env, &SCHEMA_VERSION);
     }
 
-    /// Initializes the contract and sets the admin address.
+    /// Initializes the contract and sets the admin address and transfer policy.
     /// Can only be called once.
-    pub fn initialize(env: Env, admin: Address) -> Result<(), Error> {
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        transfer_policy: TransferPolicy,
+    ) -> Result<(), Error> {
         if env.storage().instance().has(&ADMIN_KEY) {
             return Err(Error::AlreadyInitialized);
         }
         admin.require_auth();
         Storage::set_admin(&env, &admin);
-        Storage::set_issuers(&env, &Vec::<BytesN<65>>::new(&env));
+        Storage::set_transfer_policy(&env, &transfer_policy);
+        Storage::set_issuers(&env, &Vec::<u32>::new(&env));
         Storage::set_token_counter(&env, &0u32);
         Ok(())
     }
@@ -38,7 +45,7 @@ impl CertificateNFT {
         let current_admin = Storage::get_admin(&env);
         current_admin.require_auth();
         Storage::set_pending_admin(&env, &new_admin);
-        Events::admin_transfer_initiated(&env, &new_admin);
+        Events::admin_proposed(&env, &current_admin, &new_admin);
         Ok(())
     }
 
@@ -49,124 +56,641 @@ impl CertificateNFT {
 
         pending_admin.require_auth();
 
+        let previous_admin = Storage::get_admin(&env);
+
         Storage::set_admin(&env, &pending_admin);
         Storage::clear_pending_admin(&env);
 
-        Events::admin_transfer_completed(&env, &pending_admin);
+        Events::admin_accepted(&env, &previous_admin, &pending_admin);
         Ok(())
     }
 
-    /// Adds a new issuer. Only callable by the admin.
-    pub fn add_issuer(env: Env, issuer: BytesN<65>) -> Result<(), Error> {
+    /// Grants an operator role to `account`. Only callable by the admin.
+    pub fn grant_role(env: Env, role: Role, account: Address) -> Result<(), Error> {
         let admin = Storage::get_admin(&env);
 
         admin.require_auth();
 
-        let mut issuers = Storage::get_issuers(&env);
+        access_control::grant_role(&env, &role, &account, &admin);
 
-        if issuers.contains(&issuer) {
-            panic_with_error!(&env, Error::IssuerAlreadySet);
+        Ok(())
+    }
+
+    /// Revokes an operator role from `account`. Only callable by the admin.
+    pub fn revoke_role(env: Env, role: Role, account: Address) -> Result<(), Error> {
+        let admin = Storage::get_admin(&env);
+
+        admin.require_auth();
+
+        access_control::revoke_role(&env, &role, &account, &admin);
+
+        Ok(())
+    }
+
+    /// Returns true if `address` has been granted `role`
+    pub fn has_role(env: Env, role: Role, address: Address) -> bool {
+        access_control::has_role(&env, &role, &address)
+    }
+
+    /// Replaces the contract code with the uploaded wasm `new_wasm_hash`. Storage is
+    /// kept as is; call `migrate` afterwards if the new code uses a newer schema.
+    /// Only callable by the admin or an upgrader.
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        Self::require_role(&env, &caller, Role::Upgrader);
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        Events::upgraded(&env, &new_wasm_hash);
+
+        Ok(())
+    }
+
+    /// Migrates storage written by an older version of the contract to the current
+    /// schema. Large contracts are migrated over several calls; returns true once
+    /// the migration is complete. Only callable by the admin or an upgrader.
+    pub fn migrate(env: Env, caller: Address) -> Result<bool, Error> {
+        Self::require_role(&env, &caller, Role::Upgrader);
+
+        if Storage::get_schema_version(&env) >= SCHEMA_VERSION {
+            return Ok(true);
         }
 
-        if !issuers.contains(&issuer) {
-            issuers.push_back(issuer);
+        // version 1 is the only older layout
+        if !Self::migrate_v1(&env) {
+            return Ok(false);
         }
 
-        Storage::set_issuers(&env, &issuers);
+        Storage::set_schema_version(&env, &SCHEMA_VERSION);
+
+        Events::migrated(&env, SCHEMA_VERSION);
+
+        Ok(true)
+    }
+
+    /// Returns the storage schema version of the contract
+    pub fn version(env: Env) -> u32 {
+        Storage::get_schema_version(&env)
+    }
+
+    /// Returns the domain separator of this contract's typed certificate messages,
+    /// see `build_typed_certificate_message`
+    pub fn domain_separator(env: Env) -> BytesN<32> {
+        build_domain_separator(&env, &env.current_contract_address())
+    }
 
-        Events::add_issuer(&env, issuers.len());
+    /// Pauses `operation`, or every operation when `None`. Only callable by the
+    /// admin or a pauser.
+    pub fn pause(env: Env, caller: Address, operation: Option<Operation>) -> Result<(), Error> {
+        Self::require_role(&env, &caller, Role::Pauser);
+
+        let mut state = Storage::get_pause_state(&env);
+
+        match operation {
+            None => state.global = true,
+            Some(operation) => {
+                if !state.operations.contains(operation) {
+                    state.operations.push_back(operation);
+                }
+            }
+        }
+
+        Storage::set_pause_state(&env, &state);
+
+        Events::paused(&env, &caller, &state);
 
         Ok(())
     }
 
-    /// Removes an existing issuer. Only callable by the admin.
-    pub fn remove_issuer(env: Env, issuer: BytesN<65>) -> Result<(), Error> {
-        let admin = Storage::get_admin(&env);
+    /// Unpauses `operation`, or lifts the global pause when `None`. Operations
+    /// paused individually stay paused after a global unpause. Only callable by
+    /// the admin or a pauser.
+    pub fn unpause(env: Env, caller: Address, operation: Option<Operation>) -> Result<(), Error> {
+        Self::require_role(&env, &caller, Role::Pauser);
 
-        admin.require_auth();
+        let mut state = Storage::get_pause_state(&env);
 
-        let mut issuers = Storage::get_issuers(&env);
+        match operation {
+            None => state.global = false,
+            Some(operation) => {
+                if let Some(index) = state.operations.first_index_of(operation) {
+                    state.operations.remove(index);
+                }
+            }
+        }
+
+        Storage::set_pause_state(&env, &state);
+
+        Events::unpaused(&env, &caller, &state);
+
+        Ok(())
+    }
+
+    /// Returns the pause state of the contract
+    pub fn paused(env: Env) -> PauseState {
+        Storage::get_pause_state(&env)
+    }
+
+    /// Registers a new issuer identity with its profile and first key, and returns
+    /// the identity's ID. Only callable by the admin or an issuer manager.
+    /// Adding the key a removed identity was removed with restores that identity,
+    /// with a new validity period for the key.
+    pub fn add_issuer(
+        env: Env,
+        caller: Address,
+        issuer: Issuer,
+        profile: IssuerProfile,
+    ) -> Result<u32, Error> {
+        Self::require_role(&env, &caller, Role::IssuerManager);
+        Self::when_migrated(&env);
+
+        // keys never move to another identity, so that the history of an identity
+        // stays unambiguous
+        let issuer_id = match Storage::get_issuer_id(&env, &issuer) {
+            None => Self::register_issuer(&env, &issuer, &profile),
+            Some(issuer_id) if Self::restore_issuer(&env, &issuer_id, &issuer, &profile) => {
+                issuer_id
+            }
+            Some(_) => panic_with_error!(&env, Error::IssuerAlreadySet),
+        };
+
+        Events::issuer_added(&env, issuer_id, &issuer, &profile);
+
+        Ok(issuer_id)
+    }
+
+    /// Replaces the profile of a registered issuer, e.g. to change its scopes,
+    /// quota or active flag. Only callable by the admin or an issuer manager.
+    pub fn update_issuer(
+        env: Env,
+        caller: Address,
+        issuer: Issuer,
+        profile: IssuerProfile,
+    ) -> Result<(), Error> {
+        Self::require_role(&env, &caller, Role::IssuerManager);
+        Self::when_migrated(&env);
+
+        let issuer_id = Self::registered_issuer_id(&env, &issuer)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::IssuerNotFound));
+
+        Storage::set_issuer_profile(&env, &issuer_id, &profile);
+
+        Events::issuer_updated(&env, issuer_id, &profile);
+
+        Ok(())
+    }
 
-        if let Some(index) = issuers.first_index_of(&issuer) {
+    /// Removes an existing issuer. Only callable by the admin or an issuer manager.
+    /// Its current key stops being valid, and its certificates fail verification with
+    /// `IssuerNotTrusted`.
+    pub fn remove_issuer(env: Env, caller: Address, issuer: Issuer) -> Result<(), Error> {
+        Self::require_role(&env, &caller, Role::IssuerManager);
+        Self::when_migrated(&env);
+
+        let issuer_id = Self::registered_issuer_id(&env, &issuer)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::IssuerNotFound));
+
+        let mut issuers = Storage::get_issuers(&env);
+        if let Some(index) = issuers.first_index_of(issuer_id) {
             issuers.remove(index);
-        } else {
-            panic_with_error!(&env, Error::IssuerNotFound)
         }
 
+        let retired_key = Self::retire_current_key(&env, &issuer_id);
+
         Storage::set_issuers(&env, &issuers);
+        Storage::remove_issuer_profile(&env, &issuer_id);
+
+        Events::issuer_removed(&env, issuer_id, &retired_key);
+
+        Ok(())
+    }
+
+    /// Replaces the active key of an issuer identity with `new_key`. The current key
+    /// must sign the message built by `build_rotation_message`, or authorize the
+    /// call with an empty signature for account issuers (see `verify_signature` on
+    /// mismatches). Certificates signed by the old key before the rotation remain
+    /// verifiable.
+    pub fn rotate_issuer_key(
+        env: Env,
+        issuer_id: u32,
+        new_key: Issuer,
+        signature_by_old_key: Bytes,
+    ) -> Result<(), Error> {
+        Self::when_migrated(&env);
+
+        if Storage::get_issuer_profile(&env, &issuer_id).is_none() {
+            panic_with_error!(&env, Error::IssuerNotFound);
+        }
+
+        if Storage::get_issuer_id(&env, &new_key).is_some() {
+            panic_with_error!(&env, Error::IssuerAlreadySet);
+        }
 
-        Events::remove_issuer(&env, issuers.len());
+        let old_key = Self::retire_current_key(&env, &issuer_id);
+
+        let message =
+            build_rotation_message(&env, &env.current_contract_address(), &issuer_id, &new_key);
+
+        if !Self::authenticate_issuer(&env, &old_key, &signature_by_old_key, &message) {
+            panic_with_error!(&env, Error::InvalidSignature);
+        }
+
+        let mut keys = Storage::get_issuer_keys(&env, &issuer_id);
+        keys.push_back(IssuerKey {
+            key: new_key.clone(),
+            valid_from: env.ledger().timestamp(),
+            valid_until: None,
+        });
+
+        Storage::set_issuer_id(&env, &new_key, &issuer_id);
+        Storage::set_issuer_keys(&env, &issuer_id, &keys);
+
+        Events::issuer_key_rotated(&env, issuer_id, &KeyRotatedEvent { old_key, new_key });
 
         Ok(())
     }
 
+    /// Returns the ID of the issuer identity `key` belongs or belonged to
+    pub fn issuer_id(env: Env, key: Issuer) -> Option<u32> {
+        Storage::get_issuer_id(&env, &key)
+    }
+
+    /// Returns the keys of an issuer identity with their validity periods, oldest first
+    pub fn issuer_keys(env: Env, issuer_id: u32) -> Vec<IssuerKey> {
+        Storage::get_issuer_keys(&env, &issuer_id)
+    }
+
+    /// Returns the profile of the registered issuer `issuer` is or was a key of
+    pub fn issuer_profile(env: Env, issuer: Issuer) -> Option<IssuerProfile> {
+        Storage::get_issuer_id(&env, &issuer)
+            .and_then(|issuer_id| Storage::get_issuer_profile(&env, &issuer_id))
+    }
+
+    /// Returns up to `limit` registered issuer identities with their profiles,
+    /// starting at position `start` in registration order
+    pub fn list_issuers(env: Env, start: u32, limit: u32) -> Vec<(u32, IssuerProfile)> {
+        let issuers = Storage::get_issuers(&env);
+        let end = issuers
+            .len()
+            .min(start.saturating_add(limit.min(MAX_PAGE_SIZE)));
+
+        let mut entries = Vec::new(&env);
+        for index in start..end {
+            let issuer_id = issuers.get_unchecked(index);
+            if let Some(profile) = Storage::get_issuer_profile(&env, &issuer_id) {
+                entries.push_back((issuer_id, profile));
+            }
+        }
+
+        entries
+    }
+
     /// Mints a new certificate NFT. Only authorized issuers can mint.
-    /// Signature is verified against a deterministic messagenv.
-    pub fn mint_certificate(
+    /// Key issuers sign a deterministic message covering the metadata hash and
+    /// the claims; account issuers authorize the call with `require_auth` and pass
+    /// an empty signature (see `verify_signature` on mismatches).
+    pub fn mint_certificate(env: Env, request: MintRequest) -> Result<u32, Error> {
+        Self::when_not_paused(&env, Operation::Mint);
+
+        Self::mint(&env, request, None, &mut Vec::new(&env))
+    }
+
+    /// Mints a corrected successor of a certificate, e.g. to fix a grade or a typo.
+    /// `request` must come from a key of the predecessor's issuer, be addressed to
+    /// its current owner and be signed over the message built by
+    /// `build_reissue_message`. The predecessor is marked superseded.
+    pub fn reissue_certificate(
         env: Env,
-        recipient: Address,
-        course_id: String,
-        metadata_uri: String,
-        issuer: BytesN<65>,
-        issued_date: u64,
-        signature: BytesN<65>,
+        previous_id: u32,
+        request: MintRequest,
     ) -> Result<u32, Error> {
-        let issuers = Storage::get_issuers(&env);
+        Self::when_not_paused(&env, Operation::Mint);
+
+        let owner = Storage::get_certificate_owner(&env, &previous_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound));
+        let previous = Storage::get_certificate_metadata(&env, &previous_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound));
+
+        if Storage::get_revocation(&env, &previous_id).is_some() {
+            panic_with_error!(&env, Error::CertificateRevoked);
+        }
+
+        if Storage::get_next_certificate(&env, &previous_id).is_some() {
+            panic_with_error!(&env, Error::CertificateSuperseded);
+        }
 
-        if !issuers.contains(&issuer) {
+        if request.recipient != owner {
+            panic_with_error!(&env, Error::InvalidData);
+        }
+
+        if Storage::get_issuer_id(&env, &request.issuer)
+            != Storage::get_issuer_id(&env, &previous.issuer)
+        {
             panic_with_error!(&env, Error::NotIssuer);
         }
 
-        let mut certificate_id = Storage::get_token_counter(&env);
+        // link first, so the predecessor no longer counts as a course completion
+        let certificate_id = Storage::get_token_counter(&env);
+        Storage::link_reissue(&env, &previous_id, &certificate_id);
 
-        // Construct the canonical message to be signed
-        let message =
-            build_certificate_message(&env, &recipient, &course_id, &metadata_uri, &issued_date);
+        Self::mint(&env, request, Some(previous_id), &mut Vec::new(&env))?;
 
-        // Validate that the provided signature matches the message and issuer pubkey
-        if !verify_issuer_signature(&env, &issuer, &signature, &message) {
-            panic_with_error!(&env, Error::InvalidSignature);
+        Events::reissued(&env, previous_id, certificate_id);
+
+        Ok(certificate_id)
+    }
+
+    /// Returns the IDs of every version of a certificate, oldest first
+    pub fn certificate_history(env: Env, certificate_id: u32) -> Vec<u32> {
+        if Storage::get_certificate_metadata(&env, &certificate_id).is_none()
+            && Storage::get_burn_record(&env, &certificate_id).is_none()
+        {
+            panic_with_error!(&env, Error::CertificateNotFound);
         }
 
-        let metadata = CertificateMetadata {
-            course_id,
-            issued_date,
-            issuer: issuer.clone(),
-            metadata_uri,
-            signature,
-            recipient: recipient.clone(),
+        let mut first = certificate_id;
+        while let Some(previous_id) = Storage::get_previous_certificate(&env, &first) {
+            first = previous_id;
+        }
+
+        let mut history = vec![&env, first];
+        let mut current = first;
+        while let Some(next_id) = Storage::get_next_certificate(&env, &current) {
+            history.push_back(next_id);
+            current = next_id;
+        }
+
+        history
+    }
+
+    /// Mints a batch of certificates, each authenticated like `mint_certificate`.
+    /// If `atomic` is true the whole batch fails when any request is invalid;
+    /// otherwise invalid requests are skipped and reported in the results.
+    /// Account issuers authorize the batch once for all of their requests.
+    /// Batches are limited to `MAX_BATCH_SIZE` requests. A signature mismatch the host
+    /// aborts on aborts the whole batch.
+    pub fn batch_mint_certificates(
+        env: Env,
+        requests: Vec<MintRequest>,
+        atomic: bool,
+    ) -> Result<Vec<MintResult>, Error> {
+        Self::when_not_paused(&env, Operation::Mint);
+
+        if requests.is_empty() {
+            panic_with_error!(&env, Error::InvalidData);
+        }
+
+        if requests.len() > MAX_BATCH_SIZE {
+            panic_with_error!(&env, Error::BatchTooLarge);
+        }
+
+        let mut results = Vec::new(&env);
+        let mut authorized = Vec::new(&env);
+
+        for request in requests.iter() {
+            match Self::mint(&env, request, None, &mut authorized) {
+                Ok(certificate_id) => results.push_back(MintResult::Minted(certificate_id)),
+                Err(error) if !atomic => results.push_back(MintResult::Failed(error as u32)),
+                Err(error) => panic_with_error!(&env, error),
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Verifies a stored certificate. The message its issuer signed is rebuilt from
+    /// the stored metadata, so verifiers only need the certificate ID. Every failed
+    /// check is reported in the result's `reasons` (see `verify_signature` on
+    /// mismatches).
+    pub fn verify_certificate(env: Env, certificate_id: u32) -> Result<VerificationResult, Error> {
+        let mut reasons = Vec::new(&env);
+
+        if Storage::get_burn_record(&env, &certificate_id).is_some() {
+            reasons.push_back(VerificationFailure::Burned);
+            return Ok(VerificationResult {
+                valid: false,
+                reasons,
+            });
+        }
+
+        let cert = Storage::get_certificate_metadata(&env, &certificate_id)
+            .ok_or_else(|| panic_with_error!(&env, Error::CertificateNotFound))?;
+
+        if Storage::get_revocation(&env, &certificate_id).is_some() {
+            reasons.push_back(VerificationFailure::Revoked);
+        }
+
+        if Storage::get_next_certificate(&env, &certificate_id).is_some() {
+            reasons.push_back(VerificationFailure::Superseded);
+        }
+
+        let now = env.ledger().timestamp();
+
+        if cert.valid_from.is_some_and(|from| now < from) {
+            reasons.push_back(VerificationFailure::NotYetValid);
+        }
+
+        if cert.expires_at.is_some_and(|expiry| now > expiry) {
+            reasons.push_back(VerificationFailure::Expired);
+        }
+
+        if Self::registered_issuer_id(&env, &cert.issuer).is_none() {
+            reasons.push_back(VerificationFailure::IssuerNotTrusted);
+        }
+
+        // The signing key must have been its issuer's active key on the issue date
+        if !Self::issuer_key_active_at(&env, &cert.issuer, cert.issued_date) {
+            reasons.push_back(VerificationFailure::IssuerKeyInactive);
+        }
+
+        if !Self::signature_matches(&env, &certificate_id, &cert) {
+            reasons.push_back(VerificationFailure::InvalidSignature);
+        }
+
+        Ok(VerificationResult {
+            valid: reasons.is_empty(),
+            reasons,
+        })
+    }
+
+    /// Verifies a certificate presented by its holder. On top of the checks of
+    /// `verify_certificate`, the current owner must prove control of their wallet:
+    /// Stellar accounts sign the message built by `build_presentation_message`
+    /// over the verifier's `challenge`, contract wallets authorize the call and
+    /// pass an empty signature. The host aborts the call if a Stellar account
+    /// signature does not verify; `InvalidHolderProof` is reported when the owner
+    /// has no account key to check a signature against.
+    pub fn verify_presentation(
+        env: Env,
+        certificate_id: u32,
+        challenge: Bytes,
+        holder_signature: Bytes,
+    ) -> Result<VerificationResult, Error> {
+        let mut result = Self::verify_certificate(env.clone(), certificate_id)?;
+
+        // burned certificates have no holder left to prove anything
+        let Some(owner) = Storage::get_certificate_owner(&env, &certificate_id) else {
+            return Ok(result);
         };
 
-        Storage::set_certificate_metadata(&env, &certificate_id, &metadata);
+        let message = build_presentation_message(
+            &env,
+            &env.current_contract_address(),
+            &certificate_id,
+            &challenge,
+        );
+
+        if !Self::authenticate_holder(&env, &owner, &holder_signature, &message) {
+            result
+                .reasons
+                .push_back(VerificationFailure::InvalidHolderProof);
+            result.valid = false;
+        }
 
-        Storage::set_certificate_owner(&env, &certificate_id, &recipient);
+        Ok(result)
+    }
 
-        Storage::register_new_certificate(&env, &recipient);
+    /// Returns true if the certificate is not revoked and `timestamp` falls within
+    /// its validity window
+    pub fn is_valid_at(env: Env, certificate_id: u32, timestamp: u64) -> bool {
+        let cert = Storage::get_certificate_metadata(&env, &certificate_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound));
 
-        certificate_id += 1;
+        Storage::get_revocation(&env, &certificate_id).is_none() && cert.is_valid_at(timestamp)
+    }
 
-        Storage::set_token_counter(&env, &certificate_id);
+    /// Revokes a certificate. Only callable by the admin or a revoker.
+    pub fn revoke_certificate(
+        env: Env,
+        caller: Address,
+        certificate_id: u32,
+        reason: String,
+    ) -> Result<(), Error> {
+        Self::require_role(&env, &caller, Role::Revoker);
 
-        Events::mint(&env, &recipient, certificate_id);
+        Self::revoke(&env, certificate_id, &reason, Revoker::Operator(caller));
 
-        Ok(certificate_id - 1u32)
+        Ok(())
     }
 
-    /// Verifies a stored certificate by checking its original signature and public key
-    pub fn verify_certificate(
+    /// Revokes a certificate on behalf of its issuer: the issuer's current key signs
+    /// the revocation message, account issuers pass an empty signature and
+    /// authorize the call (see `verify_signature` on mismatches).
+    pub fn issuer_revoke_certificate(
         env: Env,
         certificate_id: u32,
-        certificate_data: Bytes,
-    ) -> Result<bool, Error> {
+        reason: String,
+        issuer_signature: Bytes,
+    ) -> Result<(), Error> {
         let cert = Storage::get_certificate_metadata(&env, &certificate_id)
-            .ok_or_else(|| panic_with_error!(&env, Error::CertificateNotFound))?;
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound));
+
+        let issuer = Storage::get_issuer_id(&env, &cert.issuer)
+            .and_then(|issuer_id| Self::current_key(&env, &issuer_id))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotIssuer));
+
+        let message = build_revocation_message(
+            &env,
+            &env.current_contract_address(),
+            &certificate_id,
+            &reason,
+        );
+
+        if !Self::authenticate_issuer(&env, &issuer, &issuer_signature, &message) {
+            panic_with_error!(&env, Error::InvalidSignature);
+        }
+
+        Self::revoke(&env, certificate_id, &reason, Revoker::Issuer(issuer));
+
+        Ok(())
+    }
 
-        Ok(verify_issuer_signature(
+    /// Returns the revocation record of a certificate, if it has been revoked
+    pub fn revocation(env: Env, certificate_id: u32) -> Option<RevocationRecord> {
+        Storage::get_revocation(&env, &certificate_id)
+    }
+
+    /// Destroys a certificate at the request of its owner. A tombstone with the
+    /// issuer and course is kept so verifiers can tell the certificate was burned.
+    pub fn burn(env: Env, owner: Address, certificate_id: u32) -> Result<(), Error> {
+        owner.require_auth();
+
+        Self::when_not_paused(&env, Operation::Transfer);
+
+        let current_owner = Storage::get_certificate_owner(&env, &certificate_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound));
+
+        if current_owner != owner {
+            panic_with_error!(&env, Error::NotOwner);
+        }
+
+        let metadata = Storage::get_certificate_metadata(&env, &certificate_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound));
+
+        let record = BurnRecord {
+            issuer: metadata.issuer,
+            course_id: metadata.course_id,
+            burned_at_ledger: env.ledger().sequence(),
+        };
+
+        Storage::remove_approval(&env, &certificate_id);
+        Storage::unregister_certificate(&env, &owner, &certificate_id);
+        Storage::burn_certificate(&env, &certificate_id, &record);
+
+        Events::burned(&env, &owner, certificate_id, &record);
+
+        Ok(())
+    }
+
+    /// Returns the tombstone of a certificate, if its holder burned it
+    pub fn burn_record(env: Env, certificate_id: u32) -> Option<BurnRecord> {
+        Storage::get_burn_record(&env, &certificate_id)
+    }
+
+    /// Returns true if `nonce` has already been used by `issuer` to mint a certificate
+    pub fn is_nonce_used(env: Env, issuer: Issuer, nonce: u64) -> bool {
+        Storage::is_nonce_consumed(&env, &issuer, &nonce)
+    }
+
+    /// Extends the TTL of a certificate's storage entries. Callable by anyone.
+    pub fn bump_certificate(env: Env, certificate_id: u32) -> Result<(), Error> {
+        if Storage::get_certificate_owner(&env, &certificate_id).is_none()
+            && Storage::get_burn_record(&env, &certificate_id).is_none()
+        {
+            panic_with_error!(&env, Error::CertificateNotFound);
+        }
+
+        Storage::bump_certificate(&env, &certificate_id);
+        Storage::extend_instance(&env);
+
+        Ok(())
+    }
+
+    /// Sets the TTL thresholds used when bumping certificate entries. Only callable by the admin.
+    pub fn set_ttl_config(env: Env, threshold: u32, extend_to: u32) -> Result<(), Error> {
+        let admin = Storage::get_admin(&env);
+
+        admin.require_auth();
+
+        if threshold > extend_to || extend_to > env.storage().max_ttl() {
+            panic_with_error!(&env, Error::InvalidData);
+        }
+
+        Storage::set_ttl_config(
             &env,
-            &cert.issuer,
-            &cert.signature,
-            &certificate_data,
-        ))
+            &TtlConfig {
+                threshold,
+                extend_to,
+            },
+        );
+
+        Events::ttl_config_set(&env, threshold, extend_to);
+
+        Ok(())
+    }
+
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        Storage::get_ttl_config(&env)
     }
 
     pub fn name(env: Env) -> String {
@@ -188,17 +712,929 @@ impl CertificateNFT {
 
     /// Retrieves the metadata of a certificate given its token ID
     pub fn certificate_metadata(env: Env, certificate_id: u32) -> Result<CertificateDetail, Error> {
-        let owner = Storage::get_certificate_owner(&env, &certificate_id)
-            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound));
+        Ok(Self::certificate_detail(&env, certificate_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound)))
+    }
 
-        let metadata = Storage::get_certificate_metadata(&env, &certificate_id)
-            .unwrap_or_else(|| panic!("Metadata not found"));
+    /// Returns the issuer-signed claim `key` of a certificate, if present, so other
+    /// contracts can act on e.g. a grade without fetching the metadata URI
+    pub fn claim(env: Env, certificate_id: u32, key: Symbol) -> Option<ClaimValue> {
+        if Storage::get_certificate_metadata(&env, &certificate_id).is_none() {
+            panic_with_error!(&env, Error::CertificateNotFound);
+        }
+
+        Storage::get_certificate_claims(&env, &certificate_id).get(key)
+    }
+
+    /// Returns true if `content` hashes, with `algorithm`, to the metadata hash the
+    /// issuer committed to. Always false for migrated certificates without one.
+    pub fn verify_metadata(
+        env: Env,
+        certificate_id: u32,
+        content: Bytes,
+        algorithm: HashAlgorithm,
+    ) -> bool {
+        let cert = Storage::get_certificate_metadata(&env, &certificate_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound));
 
-        Ok(CertificateDetail { owner, metadata })
+        !is_zero_hash(&cert.metadata_hash)
+            && hash_document(&env, &content, &algorithm) == cert.metadata_hash
     }
 
     /// Returns number of certificates issued to user
     pub fn user_issued_certificates(env: Env, address: Address) -> u32 {
         Storage::certificates_issued_to_user(&env, &address)
     }
+
+    /// Returns up to `limit` IDs of the certificates held by `owner`, starting at
+    /// position `start` of the owner's index. The order of the index changes when
+    /// certificates leave the owner.
+    pub fn tokens_of_owner(env: Env, owner: Address, start: u32, limit: u32) -> Vec<u32> {
+        let count = Storage::certificates_issued_to_user(&env, &owner);
+        let end = count.min(start.saturating_add(limit.min(MAX_PAGE_SIZE)));
+
+        let mut certificate_ids = Vec::new(&env);
+        for index in start..end {
+            if let Some(certificate_id) = Storage::get_owned_certificate(&env, &owner, &index) {
+                certificate_ids.push_back(certificate_id);
+            }
+        }
+
+        certificate_ids
+    }
+
+    /// Returns up to `limit` IDs of the certificates issued for `course_id`,
+    /// starting at position `start` in issuance order
+    pub fn certificates_for_course(
+        env: Env,
+        course_id: String,
+        start: u32,
+        limit: u32,
+    ) -> Vec<u32> {
+        let count = Storage::get_course_certificate_count(&env, &course_id);
+        let end = count.min(start.saturating_add(limit.min(MAX_PAGE_SIZE)));
+
+        let mut certificate_ids = Vec::new(&env);
+        for index in start..end {
+            if let Some(certificate_id) = Storage::get_course_certificate(&env, &course_id, &index)
+            {
+                certificate_ids.push_back(certificate_id);
+            }
+        }
+
+        certificate_ids
+    }
+
+    /// Returns true if `recipient` was issued a certificate for `course_id` that has
+    /// not been revoked
+    pub fn has_completed(env: Env, recipient: Address, course_id: String) -> bool {
+        Self::has_completed_course(&env, &recipient, &course_id)
+    }
+
+    /// Forbids or allows issuing a course more than once to the same recipient.
+    /// Only callable by the admin.
+    pub fn set_unique_completion(env: Env, unique: bool) -> Result<(), Error> {
+        let admin = Storage::get_admin(&env);
+
+        admin.require_auth();
+
+        Storage::set_unique_completion(&env, &unique);
+
+        Events::unique_completion_set(&env, unique);
+
+        Ok(())
+    }
+
+    pub fn unique_completion(env: Env) -> bool {
+        Storage::get_unique_completion(&env)
+    }
+
+    /// Returns the details of up to `limit` certificates, starting at certificate ID `start`
+    pub fn certificates(env: Env, start: u32, limit: u32) -> Vec<CertificateDetail> {
+        let count = Storage::get_token_counter(&env);
+        let end = count.min(start.saturating_add(limit.min(MAX_PAGE_SIZE)));
+
+        let mut certificates = Vec::new(&env);
+        for certificate_id in start..end {
+            if let Some(detail) = Self::certificate_detail(&env, certificate_id) {
+                certificates.push_back(detail);
+            }
+        }
+
+        certificates
+    }
+
+    /// Returns the number of certificates currently held by `owner`
+    pub fn balance(env: Env, owner: Address) -> u32 {
+        Storage::certificates_issued_to_user(&env, &owner)
+    }
+
+    /// Returns the metadata URI of a certificate
+    pub fn token_uri(env: Env, certificate_id: u32) -> String {
+        Storage::get_certificate_metadata(&env, &certificate_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound))
+            .metadata_uri
+    }
+
+    /// Transfers a certificate from `from` to `to`. Requires `from` to be the owner.
+    pub fn transfer(
+        env: Env,
+        from: Address,
+        to: Address,
+        certificate_id: u32,
+    ) -> Result<(), Error> {
+        from.require_auth();
+
+        Self::enforce_transfer_policy(&env, false);
+
+        Self::transfer_certificate(&env, &from, &to, certificate_id);
+
+        Ok(())
+    }
+
+    /// Transfers a certificate on behalf of its owner. `spender` must be the owner,
+    /// the approved address for the certificate or an approved operator of the owner.
+    pub fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        certificate_id: u32,
+    ) -> Result<(), Error> {
+        spender.require_auth();
+
+        Self::enforce_transfer_policy(&env, false);
+
+        let approved = Storage::get_approval(&env, &certificate_id)
+            .is_some_and(|approval| approval.approved == spender);
+
+        if spender != from && !approved && !Storage::is_approved_for_all(&env, &from, &spender) {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        Self::transfer_certificate(&env, &from, &to, certificate_id);
+
+        Ok(())
+    }
+
+    /// Moves a certificate between owners on the admin's authority, e.g. to recover
+    /// a lost wallet. Not available when certificates are soulbound.
+    pub fn admin_transfer(
+        env: Env,
+        from: Address,
+        to: Address,
+        certificate_id: u32,
+    ) -> Result<(), Error> {
+        let admin = Storage::get_admin(&env);
+
+        admin.require_auth();
+
+        Self::enforce_transfer_policy(&env, true);
+
+        Self::transfer_certificate(&env, &from, &to, certificate_id);
+
+        Ok(())
+    }
+
+    /// Proposes moving `certificate_ids` from a lost wallet to `new_owner`. Only
+    /// callable by the admin. The new owner must accept the recovery once the
+    /// recovery delay has elapsed; until then the old owner can cancel it.
+    pub fn recover_certificates(
+        env: Env,
+        old_owner: Address,
+        new_owner: Address,
+        certificate_ids: Vec<u32>,
+    ) -> Result<(), Error> {
+        let admin = Storage::get_admin(&env);
+
+        admin.require_auth();
+
+        Self::enforce_transfer_policy(&env, true);
+
+        if certificate_ids.is_empty() {
+            panic_with_error!(&env, Error::InvalidData);
+        }
+
+        for certificate_id in certificate_ids.iter() {
+            let owner = Storage::get_certificate_owner(&env, &certificate_id)
+                .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound));
+
+            if owner != old_owner {
+                panic_with_error!(&env, Error::NotOwner);
+            }
+        }
+
+        let unlock_at = env.ledger().timestamp() + Storage::get_recovery_delay(&env);
+
+        let request = RecoveryRequest {
+            new_owner: new_owner.clone(),
+            certificate_ids: certificate_ids.clone(),
+            unlock_at,
+        };
+
+        Storage::set_pending_recovery(&env, &old_owner, &request);
+
+        Events::recovery_proposed(&env, &old_owner, &new_owner, &certificate_ids, unlock_at);
+
+        Ok(())
+    }
+
+    /// Completes a pending recovery. Only callable by the proposed new owner.
+    pub fn accept_recovery(env: Env, old_owner: Address) -> Result<(), Error> {
+        let request = Storage::get_pending_recovery(&env, &old_owner)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NoPendingRecovery));
+
+        request.new_owner.require_auth();
+
+        Self::enforce_transfer_policy(&env, true);
+
+        if env.ledger().timestamp() < request.unlock_at {
+            panic_with_error!(&env, Error::RecoveryLocked);
+        }
+
+        for certificate_id in request.certificate_ids.iter() {
+            Self::transfer_certificate(&env, &old_owner, &request.new_owner, certificate_id);
+        }
+
+        Storage::clear_pending_recovery(&env, &old_owner);
+
+        Events::recovered(
+            &env,
+            &old_owner,
+            &request.new_owner,
+            &request.certificate_ids,
+        );
+
+        Ok(())
+    }
+
+    /// Cancels a pending recovery. Only callable by the old owner.
+    pub fn cancel_recovery(env: Env, old_owner: Address) -> Result<(), Error> {
+        let request = Storage::get_pending_recovery(&env, &old_owner)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NoPendingRecovery));
+
+        old_owner.require_auth();
+
+        Storage::clear_pending_recovery(&env, &old_owner);
+
+        Events::recovery_cancelled(&env, &old_owner, &request.new_owner);
+
+        Ok(())
+    }
+
+    pub fn pending_recovery(env: Env, old_owner: Address) -> Option<RecoveryRequest> {
+        Storage::get_pending_recovery(&env, &old_owner)
+    }
+
+    /// Sets the number of seconds a recovery must wait before it can be accepted.
+    /// Only callable by the admin.
+    pub fn set_recovery_delay(env: Env, delay: u64) -> Result<(), Error> {
+        let admin = Storage::get_admin(&env);
+
+        admin.require_auth();
+
+        Storage::set_recovery_delay(&env, &delay);
+
+        Events::recovery_delay_set(&env, delay);
+
+        Ok(())
+    }
+
+    pub fn recovery_delay(env: Env) -> u64 {
+        Storage::get_recovery_delay(&env)
+    }
+
+    pub fn transfer_policy(env: Env) -> TransferPolicy {
+        Storage::get_transfer_policy(&env)
+    }
+
+    /// Approves `approved` to transfer a certificate until `live_until_ledger`.
+    /// `approver` must be the owner or an approved operator of the owner.
+    /// A `live_until_ledger` of 0 removes the approval.
+    pub fn approve(
+        env: Env,
+        approver: Address,
+        approved: Address,
+        certificate_id: u32,
+        live_until_ledger: u32,
+    ) -> Result<(), Error> {
+        approver.require_auth();
+
+        let owner = Storage::get_certificate_owner(&env, &certificate_id)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::CertificateNotFound));
+
+        if approver != owner && !Storage::is_approved_for_all(&env, &owner, &approver) {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        if live_until_ledger == 0 {
+            Storage::remove_approval(&env, &certificate_id);
+        } else {
+            Self::validate_live_until_ledger(&env, live_until_ledger);
+
+            let approval = ApprovalData {
+                approved: approved.clone(),
+                live_until_ledger,
+            };
+            Storage::set_approval(&env, &certificate_id, &approval);
+        }
+
+        Events::approve(
+            &env,
+            &approver,
+            &approved,
+            certificate_id,
+            live_until_ledger,
+        );
+
+        Ok(())
+    }
+
+    /// Approves `operator` to transfer all of `owner`'s certificates until
+    /// `live_until_ledger`. A `live_until_ledger` of 0 revokes the operator.
+    pub fn approve_for_all(
+        env: Env,
+        owner: Address,
+        operator: Address,
+        live_until_ledger: u32,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+
+        if live_until_ledger == 0 {
+            Storage::remove_approval_for_all(&env, &owner, &operator);
+        } else {
+            Self::validate_live_until_ledger(&env, live_until_ledger);
+            Storage::set_approval_for_all(&env, &owner, &operator, &live_until_ledger);
+        }
+
+        Events::approve_for_all(&env, &owner, &operator, live_until_ledger);
+
+        Ok(())
+    }
+
+    /// Returns the address approved to transfer a certificate, if any
+    pub fn get_approved(env: Env, certificate_id: u32) -> Option<Address> {
+        Storage::get_approval(&env, &certificate_id).map(|approval| approval.approved)
+    }
+
+    /// Returns true if `operator` may transfer all of `owner`'s certificates
+    pub fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool {
+        Storage::is_approved_for_all(&env, &owner, &operator)
+    }
+}
+
+impl CertificateNFT {
+    // Validates a mint request and, only if it is valid, mints the certificate,
+    // or the successor of `previous_id` when reissuing. `authorized` holds the account
+    // issuers that already authorized the current call, which the host allows only once.
+    fn mint(
+        env: &Env,
+        request: MintRequest,
+        previous_id: Option<u32>,
+        authorized: &mut Vec<Address>,
+    ) -> Result<u32, Error> {
+        let MintRequest {
+            recipient,
+            course_id,
+            metadata_uri,
+            metadata_hash,
+            issuer,
+            issued_date,
+            valid_from,
+            expires_at,
+            nonce,
+            claims,
+            message_version,
+            signature,
+        } = request;
+
+        let issuer_id = Storage::get_issuer_id(env, &issuer).ok_or(Error::NotIssuer)?;
+        let profile = Storage::get_issuer_profile(env, &issuer_id).ok_or(Error::NotIssuer)?;
+
+        // Retired keys cannot mint, and the current key cannot vouch for dates
+        // before it became active
+        if !Self::issuer_key_active_at(env, &issuer, issued_date)
+            || Self::current_key(env, &issuer_id).as_ref() != Some(&issuer)
+        {
+            return Err(Error::IssuerKeyInactive);
+        }
+
+        if !profile.active {
+            return Err(Error::IssuerInactive);
+        }
+
+        if !profile.course_scopes.is_empty()
+            && !profile
+                .course_scopes
+                .iter()
+                .any(|scope| has_prefix(env, &course_id, &scope))
+        {
+            return Err(Error::CourseNotAllowed);
+        }
+
+        let mut window = Self::current_mint_window(env, &issuer_id, &profile);
+
+        if profile.max_mints.is_some_and(|max| window.minted >= max) {
+            return Err(Error::MintQuotaExceeded);
+        }
+
+        if let (Some(from), Some(expiry)) = (valid_from, expires_at) {
+            if expiry <= from {
+                return Err(Error::InvalidData);
+            }
+        }
+
+        if claims.len() > MAX_CLAIMS || is_zero_hash(&metadata_hash) {
+            return Err(Error::InvalidData);
+        }
+
+        if Storage::is_nonce_consumed(env, &issuer, &nonce) {
+            return Err(Error::DuplicateCertificate);
+        }
+
+        if Storage::get_unique_completion(env)
+            && Self::has_completed_course(env, &recipient, &course_id)
+        {
+            return Err(Error::CourseAlreadyCompleted);
+        }
+
+        let certificate_id = Storage::get_token_counter(env);
+
+        let metadata = CertificateMetadata {
+            course_id,
+            issued_date,
+            valid_from,
+            expires_at,
+            nonce,
+            issuer: issuer.clone(),
+            metadata_uri,
+            metadata_hash,
+            signature,
+            recipient: recipient.clone(),
+        };
+
+        // Construct the canonical message to be signed
+        let message =
+            Self::certificate_message(env, &metadata, &claims, message_version, previous_id);
+
+        // Validate that the issuer signed or authorized the message
+        match &issuer {
+            Issuer::Account(address) if authorized.contains(address) => {}
+            Issuer::Account(address) => {
+                address.require_auth();
+                authorized.push_back(address.clone());
+            }
+            _ => {
+                if !verify_signature(env, &issuer, &metadata.signature, &message) {
+                    return Err(Error::InvalidSignature);
+                }
+            }
+        }
+
+        Storage::consume_nonce(env, &issuer, &nonce);
+
+        if profile.max_mints.is_some() {
+            window.minted += 1;
+            Storage::set_mint_window(env, &issuer_id, &window);
+        }
+
+        Storage::set_certificate_metadata(env, &certificate_id, &metadata);
+
+        Storage::set_certificate_claims(env, &certificate_id, &claims);
+
+        Storage::set_message_version(env, &certificate_id, &message_version);
+
+        Storage::set_certificate_owner(env, &certificate_id, &recipient);
+
+        Storage::register_new_certificate(env, &recipient, &certificate_id);
+
+        Storage::register_course_certificate(env, &metadata.course_id, &recipient, &certificate_id);
+
+        Storage::set_token_counter(env, &(certificate_id + 1));
+
+        Storage::extend_instance(env);
+
+        let payload = MintedEvent {
+            issuer_id,
+            issuer: metadata.issuer,
+            course_id: metadata.course_id,
+            metadata_uri_hash: env
+                .crypto()
+                .sha256(&string_to_bytes(env, metadata.metadata_uri))
+                .into(),
+        };
+
+        Events::minted(env, &recipient, certificate_id, &payload);
+
+        Ok(certificate_id)
+    }
+
+    // Fails with `Paused` if `operation` is paused individually or globally
+    fn when_not_paused(env: &Env, operation: Operation) {
+        Self::when_migrated(env);
+
+        let state = Storage::get_pause_state(env);
+
+        if state.global || state.operations.contains(operation) {
+            panic_with_error!(env, Error::Paused);
+        }
+    }
+
+    // Fails with `MigrationPending` until `migrate` has brought storage to the current
+    // schema, so that nothing is written next to data still in an older layout
+    fn when_migrated(env: &Env) {
+        if Storage::get_schema_version(env) < SCHEMA_VERSION {
+            panic_with_error!(env, Error::MigrationPending);
+        }
+    }
+
+    // Requires the authorization of `caller`, who must be the admin or hold `role`
+    fn require_role(env: &Env, caller: &Address, role: Role) {
+        caller.require_auth();
+
+        if *caller != Storage::get_admin(env) && !access_control::has_role(env, &role, caller) {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+    }
+
+    fn revoke(env: &Env, certificate_id: u32, reason: &String, revoked_by: Revoker) {
+        Self::when_not_paused(env, Operation::Revoke);
+
+        if Storage::get_certificate_metadata(env, &certificate_id).is_none() {
+            panic_with_error!(env, Error::CertificateNotFound);
+        }
+
+        if Storage::get_revocation(env, &certificate_id).is_some() {
+            panic_with_error!(env, Error::CertificateRevoked);
+        }
+
+        let record = RevocationRecord {
+            reason: reason.clone(),
+            revoked_at: env.ledger().timestamp(),
+            revoked_by,
+        };
+
+        Storage::set_revocation(env, &certificate_id, &record);
+
+        Events::revoked(env, certificate_id, &record);
+    }
+
+    // Creates an issuer identity whose first key vouches for certificates from any
+    // date until `valid_until`
+    fn create_issuer_identity(env: &Env, key: &Issuer, valid_until: Option<u64>) -> u32 {
+        let issuer_id = Storage::get_issuer_counter(env);
+        Storage::set_issuer_counter(env, &(issuer_id + 1));
+
+        let keys = vec![
+            env,
+            IssuerKey {
+                key: key.clone(),
+                valid_from: 0,
+                valid_until,
+            },
+        ];
+
+        Storage::set_issuer_id(env, key, &issuer_id);
+        Storage::set_issuer_keys(env, &issuer_id, &keys);
+
+        issuer_id
+    }
+
+    // Creates an issuer identity for `key` and adds it to the registry
+    fn register_issuer(env: &Env, key: &Issuer, profile: &IssuerProfile) -> u32 {
+        let issuer_id = Self::create_issuer_identity(env, key, None);
+
+        Storage::set_issuer_profile(env, &issuer_id, profile);
+
+        let mut issuers = Storage::get_issuers(env);
+        issuers.push_back(issuer_id);
+        Storage::set_issuers(env, &issuers);
+
+        issuer_id
+    }
+
+    // Adds a removed identity back to the registry if `key` is the key it was removed
+    // with, reactivating the key from now on. Returns false otherwise.
+    fn restore_issuer(env: &Env, issuer_id: &u32, key: &Issuer, profile: &IssuerProfile) -> bool {
+        let mut keys = Storage::get_issuer_keys(env, issuer_id);
+
+        if Storage::get_issuer_profile(env, issuer_id).is_some()
+            || keys.last().map(|entry| entry.key).as_ref() != Some(key)
+        {
+            return false;
+        }
+
+        keys.push_back(IssuerKey {
+            key: key.clone(),
+            valid_from: env.ledger().timestamp(),
+            valid_until: None,
+        });
+
+        Storage::set_issuer_keys(env, issuer_id, &keys);
+        Storage::set_issuer_profile(env, issuer_id, profile);
+
+        let mut issuers = Storage::get_issuers(env);
+        issuers.push_back(*issuer_id);
+        Storage::set_issuers(env, &issuers);
+
+        true
+    }
+
+    // Moves the next batch of certificates of a version 1 contract from instance to
+    // persistent storage. The first call also converts the issuer list and sets
+    // configuration introduced since. Returns true once every certificate is moved.
+    fn migrate_v1(env: &Env) -> bool {
+        let cursor = Storage::get_migration_cursor(env).unwrap_or_else(|| {
+            let legacy_issuers = Storage::take_legacy_issuers(env);
+            Storage::set_issuers(env, &Vec::new(env));
+
+            let profile = IssuerProfile {
+                name: String::from_str(env, ""),
+                website: String::from_str(env, ""),
+                metadata_uri: String::from_str(env, ""),
+                course_scopes: Vec::new(env),
+                max_mints: None,
+                mint_period: 0,
+                active: true,
+            };
+            for key in legacy_issuers.iter() {
+                Self::register_issuer(env, &Issuer::Secp256k1(key), &profile);
+            }
+
+            // version 1 certificates could not be transferred
+            if !env.storage().instance().has(&POLICY_KEY) {
+                Storage::set_transfer_policy(env, &TransferPolicy::Soulbound);
+            }
+
+            0
+        });
+
+        let count = Storage::get_token_counter(env);
+        let end = count.min(cursor.saturating_add(MIGRATION_BATCH_SIZE));
+
+        for certificate_id in cursor..end {
+            let Some((owner, legacy)) = Storage::take_legacy_certificate(env, &certificate_id)
+            else {
+                continue;
+            };
+
+            let issuer = Issuer::Secp256k1(legacy.issuer);
+
+            // give issuers removed before the migration an identity, so their
+            // certificates can still be traced to a key
+            if Storage::get_issuer_id(env, &issuer).is_none() {
+                Self::create_issuer_identity(env, &issuer, Some(env.ledger().timestamp()));
+            }
+
+            let metadata = CertificateMetadata {
+                course_id: legacy.course_id,
+                issued_date: legacy.issued_date,
+                valid_from: None,
+                expires_at: None,
+                nonce: 0,
+                issuer,
+                metadata_uri: legacy.metadata_uri,
+                metadata_hash: BytesN::from_array(env, &[0; 32]),
+                signature: legacy.signature.into(),
+                recipient: legacy.recipient,
+            };
+
+            Storage::set_certificate_metadata(env, &certificate_id, &metadata);
+            Storage::set_certificate_owner(env, &certificate_id, &owner);
+            Storage::set_legacy_certificate(env, &certificate_id);
+            Storage::register_new_certificate(env, &owner, &certificate_id);
+            Storage::register_course_certificate(
+                env,
+                &metadata.course_id,
+                &metadata.recipient,
+                &certificate_id,
+            );
+        }
+
+        if end < count {
+            Storage::set_migration_cursor(env, &end);
+            return false;
+        }
+
+        Storage::clear_migration_cursor(env);
+
+        true
+    }
+
+    // Returns true if the stored signature covers the stored metadata. Signatures were
+    // checked at mint time, so this only fails if the stored data was altered.
+    fn signature_matches(env: &Env, certificate_id: &u32, cert: &CertificateMetadata) -> bool {
+        let claims = Storage::get_certificate_claims(env, certificate_id);
+
+        let message = if Storage::is_legacy_certificate(env, certificate_id) {
+            build_legacy_certificate_message(env, cert)
+        } else {
+            Self::certificate_message(
+                env,
+                cert,
+                &claims,
+                Storage::get_message_version(env, certificate_id),
+                Storage::get_previous_certificate(env, certificate_id),
+            )
+        };
+
+        match cert.issuer {
+            // Account issuers authorized the mint on-chain and signed nothing
+            Issuer::Account(_) => true,
+            _ => verify_signature(env, &cert.issuer, &cert.signature, &message),
+        }
+    }
+
+    // Builds the message the issuer signs to mint `cert` in the format of `version`,
+    // bound to `previous_id` when reissuing
+    fn certificate_message(
+        env: &Env,
+        cert: &CertificateMetadata,
+        claims: &Map<Symbol, ClaimValue>,
+        version: MessageVersion,
+        previous_id: Option<u32>,
+    ) -> Bytes {
+        match version {
+            MessageVersion::Concatenated => {
+                let message = build_metadata_message(env, cert, claims);
+                match previous_id {
+                    Some(previous_id) => build_reissue_message(env, &previous_id, &message),
+                    None => message,
+                }
+            }
+            MessageVersion::Typed => {
+                let domain_separator = build_domain_separator(env, &env.current_contract_address());
+                build_typed_certificate_message(env, &domain_separator, cert, claims, previous_id)
+            }
+        }
+    }
+
+    // Returns the ID of the registered issuer `key` is or was a key of
+    fn registered_issuer_id(env: &Env, key: &Issuer) -> Option<u32> {
+        Storage::get_issuer_id(env, key)
+            .filter(|issuer_id| Storage::get_issuer_profile(env, issuer_id).is_some())
+    }
+
+    // Returns the active key of an issuer identity, if it has one
+    fn current_key(env: &Env, issuer_id: &u32) -> Option<Issuer> {
+        Storage::get_issuer_keys(env, issuer_id)
+            .last()
+            .filter(|key| key.valid_until.is_none())
+            .map(|key| key.key)
+    }
+
+    // Ends the validity period of the active key of an issuer identity and returns it
+    fn retire_current_key(env: &Env, issuer_id: &u32) -> Issuer {
+        let mut keys = Storage::get_issuer_keys(env, issuer_id);
+        let index = keys.len() - 1;
+        let mut current = keys.get_unchecked(index);
+
+        current.valid_until = Some(env.ledger().timestamp());
+        keys.set(index, current.clone());
+
+        Storage::set_issuer_keys(env, issuer_id, &keys);
+
+        current.key
+    }
+
+    // Returns true if `key` was the active key of its issuer identity at `timestamp`
+    fn issuer_key_active_at(env: &Env, key: &Issuer, timestamp: u64) -> bool {
+        let Some(issuer_id) = Storage::get_issuer_id(env, key) else {
+            return false;
+        };
+
+        Storage::get_issuer_keys(env, &issuer_id)
+            .iter()
+            .any(|entry| entry.key == *key && entry.is_active_at(timestamp))
+    }
+
+    // Returns the issuer's quota usage in the period containing the current ledger
+    // timestamp. Periods are aligned to multiples of `mint_period`.
+    fn current_mint_window(env: &Env, issuer_id: &u32, profile: &IssuerProfile) -> MintWindow {
+        let now = env.ledger().timestamp();
+        let started_at = match profile.mint_period {
+            0 => 0,
+            period => now - now % period,
+        };
+
+        match Storage::get_mint_window(env, issuer_id) {
+            Some(window) if window.started_at == started_at => window,
+            _ => MintWindow {
+                started_at,
+                minted: 0,
+            },
+        }
+    }
+
+    // Account issuers must authorize the current call, key issuers must have signed `message`
+    fn authenticate_issuer(env: &Env, issuer: &Issuer, signature: &Bytes, message: &Bytes) -> bool {
+        match issuer {
+            Issuer::Account(address) => {
+                address.require_auth();
+                true
+            }
+            _ => verify_signature(env, issuer, signature, message),
+        }
+    }
+
+    // Holders authorize the current call when presenting an empty signature, Stellar
+    // accounts may sign `message` with their account key instead (host aborts on mismatch)
+    fn authenticate_holder(env: &Env, owner: &Address, signature: &Bytes, message: &Bytes) -> bool {
+        if signature.is_empty() {
+            owner.require_auth();
+            return true;
+        }
+
+        match account_public_key(env, owner) {
+            Some(public_key) => {
+                verify_signature(env, &Issuer::Ed25519(public_key), signature, message)
+            }
+            None => false,
+        }
+    }
+
+    fn certificate_detail(env: &Env, certificate_id: u32) -> Option<CertificateDetail> {
+        let owner = Storage::get_certificate_owner(env, &certificate_id)?;
+
+        let metadata = Storage::get_certificate_metadata(env, &certificate_id)
+            .unwrap_or_else(|| panic!("Metadata not found"));
+
+        let now = env.ledger().timestamp();
+
+        let status = if Storage::get_revocation(env, &certificate_id).is_some() {
+            CertificateStatus::Revoked
+        } else if Storage::get_next_certificate(env, &certificate_id).is_some() {
+            CertificateStatus::Superseded
+        } else if metadata.valid_from.is_some_and(|from| now < from) {
+            CertificateStatus::NotYetValid
+        } else if metadata.expires_at.is_some_and(|expiry| now > expiry) {
+            CertificateStatus::Expired
+        } else {
+            CertificateStatus::Active
+        };
+
+        Some(CertificateDetail {
+            owner,
+            metadata,
+            claims: Storage::get_certificate_claims(env, &certificate_id),
+            message_version: Storage::get_message_version(env, &certificate_id),
+            status,
+        })
+    }
+
+    // Moves a certificate to `to` after checking `from` owns it. Authorization is
+    // the caller's responsibility.
+    fn transfer_certificate(env: &Env, from: &Address, to: &Address, certificate_id: u32) {
+        Self::when_not_paused(env, Operation::Transfer);
+
+        let owner = Storage::get_certificate_owner(env, &certificate_id)
+            .unwrap_or_else(|| panic_with_error!(env, Error::CertificateNotFound));
+
+        if owner != *from {
+            panic_with_error!(env, Error::NotOwner);
+        }
+
+        Storage::remove_approval(env, &certificate_id);
+        Storage::set_certificate_owner(env, &certificate_id, to);
+        Storage::unregister_certificate(env, from, &certificate_id);
+        Storage::register_new_certificate(env, to, &certificate_id);
+
+        Events::transfer(env, from, to, certificate_id);
+    }
+
+    // True if any certificate minted to `recipient` for the course is still live
+    fn has_completed_course(env: &Env, recipient: &Address, course_id: &String) -> bool {
+        let count = Storage::get_course_completion_count(env, recipient, course_id);
+
+        (0..count).any(|index| {
+            Storage::get_course_completion(env, recipient, course_id, &index).is_some_and(
+                |certificate_id| {
+                    Storage::get_revocation(env, &certificate_id).is_none()
+                        && Storage::get_burn_record(env, &certificate_id).is_none()
+                        && Storage::get_next_certificate(env, &certificate_id).is_none()
+                },
+            )
+        })
+    }
+
+    // Panics unless the transfer policy allows ownership changes by this kind of caller
+    fn enforce_transfer_policy(env: &Env, by_admin: bool) {
+        let allowed = match Storage::get_transfer_policy(env) {
+            TransferPolicy::Soulbound => false,
+            TransferPolicy::AdminOnly => by_admin,
+            TransferPolicy::Transferable => true,
+        };
+
+        if !allowed {
+            panic_with_error!(env, Error::TransferNotAllowed);
+        }
+    }
+
+    fn validate_live_until_ledger(env: &Env, live_until_ledger: u32) {
+        if live_until_ledger < env.ledger().sequence()
+            || live_until_ledger > env.ledger().max_live_until_ledger()
+        {
+            panic_with_error!(env, Error::InvalidData);
+        }
+    }
 }
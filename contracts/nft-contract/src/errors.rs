@@ -14,4 +14,20 @@ pub enum Error {
     IssuerNotFound = 107,
     NoPendingAdmin = 108,
     InvalidData = 109,
+    CertificateRevoked = 110,
+    // 111 and 112 were `CertificateExpired` and `CertificateNotYetValid`; do not reuse them
+    DuplicateCertificate = 113,
+    NotOwner = 114,
+    TransferNotAllowed = 115,
+    NoPendingRecovery = 116,
+    RecoveryLocked = 117,
+    CourseAlreadyCompleted = 118,
+    BatchTooLarge = 119,
+    IssuerInactive = 120,
+    CourseNotAllowed = 121,
+    MintQuotaExceeded = 122,
+    IssuerKeyInactive = 123,
+    Paused = 124,
+    CertificateSuperseded = 125,
+    MigrationPending = 126,
 }
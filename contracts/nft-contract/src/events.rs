@@ -1,30 +1,145 @@
-use soroban_sdk::{symbol_short, Address, Env};
+use crate::types::{
+    BurnRecord, Issuer, IssuerProfile, KeyRotatedEvent, MintedEvent, PauseState, RevocationRecord,
+};
+use soroban_sdk::{symbol_short, Address, BytesN, Env, Symbol, Vec};
 
+/// Every event's first topic is a distinct symbol naming the action, followed by
+/// the IDs and addresses an indexer would filter on. Everything else goes in the data.
 pub struct Events;
 
 impl Events {
-    pub fn mint(env: &Env, to: &Address, certificate_id: u32) {
-        let topics = (symbol_short!("mint"), to);
+    pub fn minted(env: &Env, to: &Address, certificate_id: u32, payload: &MintedEvent) {
+        let topics = (symbol_short!("minted"), to, certificate_id);
+        env.events().publish(topics, payload.clone());
+    }
+
+    pub fn transfer(env: &Env, from: &Address, to: &Address, certificate_id: u32) {
+        let topics = (symbol_short!("transfer"), from, to);
         env.events().publish(topics, certificate_id);
     }
 
-    pub fn add_issuer(env: &Env, count: u32) {
-        let topics = symbol_short!("issuer");
-        env.events().publish((topics,), count);
+    pub fn approve(
+        env: &Env,
+        approver: &Address,
+        approved: &Address,
+        certificate_id: u32,
+        live_until_ledger: u32,
+    ) {
+        let topics = (symbol_short!("approve"), approver, certificate_id);
+        env.events()
+            .publish(topics, (approved.clone(), live_until_ledger));
+    }
+
+    pub fn approve_for_all(env: &Env, owner: &Address, operator: &Address, live_until_ledger: u32) {
+        let topics = (Symbol::new(env, "approve_for_all"), owner);
+        env.events()
+            .publish(topics, (operator.clone(), live_until_ledger));
+    }
+
+    pub fn recovery_proposed(
+        env: &Env,
+        old_owner: &Address,
+        new_owner: &Address,
+        certificate_ids: &Vec<u32>,
+        unlock_at: u64,
+    ) {
+        let topics = (Symbol::new(env, "recovery_proposed"), old_owner, new_owner);
+        env.events()
+            .publish(topics, (certificate_ids.clone(), unlock_at));
+    }
+
+    pub fn recovery_cancelled(env: &Env, old_owner: &Address, new_owner: &Address) {
+        let topics = (Symbol::new(env, "recovery_cancelled"), old_owner, new_owner);
+        env.events().publish(topics, ());
+    }
+
+    pub fn recovered(
+        env: &Env,
+        old_owner: &Address,
+        new_owner: &Address,
+        certificate_ids: &Vec<u32>,
+    ) {
+        let topics = (symbol_short!("recovered"), old_owner, new_owner);
+        env.events().publish(topics, certificate_ids.clone());
+    }
+
+    pub fn burned(env: &Env, owner: &Address, certificate_id: u32, record: &BurnRecord) {
+        let topics = (symbol_short!("burned"), owner, certificate_id);
+        env.events().publish(topics, record.clone());
+    }
+
+    pub fn reissued(env: &Env, previous_id: u32, certificate_id: u32) {
+        let topics = (symbol_short!("reissued"), previous_id);
+        env.events().publish(topics, certificate_id);
+    }
+
+    pub fn revoked(env: &Env, certificate_id: u32, record: &RevocationRecord) {
+        let topics = (symbol_short!("revoked"), certificate_id);
+        env.events().publish(topics, record.clone());
+    }
+
+    pub fn issuer_added(env: &Env, issuer_id: u32, key: &Issuer, profile: &IssuerProfile) {
+        let topics = (Symbol::new(env, "issuer_added"), issuer_id);
+        env.events().publish(topics, (key.clone(), profile.clone()));
+    }
+
+    pub fn issuer_updated(env: &Env, issuer_id: u32, profile: &IssuerProfile) {
+        let topics = (Symbol::new(env, "issuer_updated"), issuer_id);
+        env.events().publish(topics, profile.clone());
+    }
+
+    pub fn issuer_removed(env: &Env, issuer_id: u32, key: &Issuer) {
+        let topics = (Symbol::new(env, "issuer_removed"), issuer_id);
+        env.events().publish(topics, key.clone());
+    }
+
+    pub fn issuer_key_rotated(env: &Env, issuer_id: u32, payload: &KeyRotatedEvent) {
+        let topics = (Symbol::new(env, "issuer_key_rotated"), issuer_id);
+        env.events().publish(topics, payload.clone());
+    }
+
+    pub fn paused(env: &Env, caller: &Address, state: &PauseState) {
+        let topics = (symbol_short!("paused"), caller);
+        env.events().publish(topics, state.clone());
+    }
+
+    pub fn unpaused(env: &Env, caller: &Address, state: &PauseState) {
+        let topics = (symbol_short!("unpaused"), caller);
+        env.events().publish(topics, state.clone());
+    }
+
+    pub fn upgraded(env: &Env, new_wasm_hash: &BytesN<32>) {
+        let topics = (symbol_short!("upgraded"),);
+        env.events().publish(topics, new_wasm_hash.clone());
+    }
+
+    pub fn migrated(env: &Env, version: u32) {
+        let topics = (symbol_short!("migrated"),);
+        env.events().publish(topics, version);
+    }
+
+    pub fn admin_proposed(env: &Env, current_admin: &Address, new_admin: &Address) {
+        let topics = (Symbol::new(env, "admin_proposed"), current_admin);
+        env.events().publish(topics, new_admin.clone());
+    }
+
+    pub fn admin_accepted(env: &Env, previous_admin: &Address, new_admin: &Address) {
+        let topics = (Symbol::new(env, "admin_accepted"), previous_admin);
+        env.events().publish(topics, new_admin.clone());
     }
 
-    pub fn remove_issuer(env: &Env, count: u32) {
-        let topics = symbol_short!("issuer");
-        env.events().publish((topics,), count);
+    pub fn ttl_config_set(env: &Env, threshold: u32, extend_to: u32) {
+        let topics = (Symbol::new(env, "ttl_config_set"),);
+        env.events().publish(topics, (threshold, extend_to));
     }
 
-    pub fn admin_transfer_initiated(env: &Env, new_admin: &Address) {
-        let topics = symbol_short!("admin");
-        env.events().publish((topics,), new_admin);
+    pub fn unique_completion_set(env: &Env, unique: bool) {
+        let topics = (Symbol::new(env, "unique_completion_set"),);
+        env.events().publish(topics, unique);
     }
 
-    pub fn admin_transfer_completed(env: &Env, new_admin: &Address) {
-        let topics = symbol_short!("admin");
-        env.events().publish((topics,), new_admin);
+    pub fn recovery_delay_set(env: &Env, delay: u64) {
+        let topics = (Symbol::new(env, "recovery_delay_set"),);
+        env.events().publish(topics, delay);
     }
 }
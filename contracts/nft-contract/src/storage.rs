@@ -1,8 +1,12 @@
 use crate::types::{
-    CertificateMetadata, DataKey, ADMIN_KEY, COUNTER_KEY, ISSUERS_KEY, NAME_KEY, PENDING_ADMIN,
-    SYMBOL_KEY,
+    ApprovalData, BurnRecord, CertificateMetadata, ClaimValue, DataKey, Issuer, IssuerKey,
+    IssuerProfile, LegacyCertificateMetadata, MessageVersion, MintWindow, PauseState,
+    RecoveryRequest, RevocationRecord, TransferPolicy, TtlConfig, ADMIN_KEY,
+    CERTIFICATE_BUMP_AMOUNT, CERTIFICATE_LIFETIME_THRESHOLD, COUNTER_KEY, ISSUERS_KEY,
+    ISSUER_COUNTER_KEY, MIGRATION_KEY, NAME_KEY, PAUSE_KEY, PENDING_ADMIN, POLICY_KEY,
+    RECOVERY_DELAY_KEY, SYMBOL_KEY, TTL_KEY, UNIQUE_COMPLETION_KEY, VERSION_KEY,
 };
-use soroban_sdk::{Address, BytesN, Env, String, Vec};
+use soroban_sdk::{Address, BytesN, Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec};
 
 pub struct Storage;
 
@@ -25,7 +29,7 @@ impl Storage {
     }
 
     pub fn clear_pending_admin(env: &Env) {
-        env.storage().instance().remove(&ADMIN_KEY)
+        env.storage().instance().remove(&PENDING_ADMIN)
     }
 
     pub fn set_name(env: &Env, name: &String) {
@@ -44,14 +48,92 @@ impl Storage {
         env.storage().instance().get(&SYMBOL_KEY).unwrap()
     }
 
-    pub fn set_issuers(env: &Env, issuers: &Vec<BytesN<65>>) {
+    pub fn set_transfer_policy(env: &Env, policy: &TransferPolicy) {
+        env.storage().instance().set(&POLICY_KEY, policy);
+    }
+
+    pub fn get_transfer_policy(env: &Env) -> TransferPolicy {
+        env.storage().instance().get(&POLICY_KEY).unwrap()
+    }
+
+    pub fn set_recovery_delay(env: &Env, delay: &u64) {
+        env.storage().instance().set(&RECOVERY_DELAY_KEY, delay);
+    }
+
+    pub fn get_recovery_delay(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&RECOVERY_DELAY_KEY)
+            .unwrap_or(0u64)
+    }
+
+    pub fn set_unique_completion(env: &Env, unique: &bool) {
+        env.storage().instance().set(&UNIQUE_COMPLETION_KEY, unique);
+    }
+
+    pub fn get_unique_completion(env: &Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&UNIQUE_COMPLETION_KEY)
+            .unwrap_or(false)
+    }
+
+    pub fn set_schema_version(env: &Env, version: &u32) {
+        env.storage().instance().set(&VERSION_KEY, version);
+    }
+
+    // Contracts deployed before versioning was introduced use version 1
+    pub fn get_schema_version(env: &Env) -> u32 {
+        env.storage().instance().get(&VERSION_KEY).unwrap_or(1)
+    }
+
+    // Next certificate ID to migrate, set while a migration is in progress
+    pub fn set_migration_cursor(env: &Env, certificate_id: &u32) {
+        env.storage().instance().set(&MIGRATION_KEY, certificate_id);
+    }
+
+    pub fn get_migration_cursor(env: &Env) -> Option<u32> {
+        env.storage().instance().get(&MIGRATION_KEY)
+    }
+
+    pub fn clear_migration_cursor(env: &Env) {
+        env.storage().instance().remove(&MIGRATION_KEY)
+    }
+
+    pub fn set_pause_state(env: &Env, state: &PauseState) {
+        env.storage().instance().set(&PAUSE_KEY, state);
+    }
+
+    pub fn get_pause_state(env: &Env) -> PauseState {
+        env.storage()
+            .instance()
+            .get(&PAUSE_KEY)
+            .unwrap_or(PauseState {
+                global: false,
+                operations: Vec::new(env),
+            })
+    }
+
+    // Registered issuer identities, in registration order
+    pub fn set_issuers(env: &Env, issuers: &Vec<u32>) {
         env.storage().instance().set(&ISSUERS_KEY, issuers);
     }
 
-    pub fn get_issuers(env: &Env) -> Vec<BytesN<65>> {
+    pub fn get_issuers(env: &Env) -> Vec<u32> {
         env.storage().instance().get(&ISSUERS_KEY).unwrap()
     }
 
+    pub fn set_issuer_counter(env: &Env, count: &u32) {
+        env.storage().instance().set(&ISSUER_COUNTER_KEY, count);
+    }
+
+    pub fn get_issuer_counter(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&ISSUER_COUNTER_KEY)
+            .unwrap_or(0)
+    }
+
     pub fn set_token_counter(env: &Env, count: &u32) {
         env.storage().instance().set(&COUNTER_KEY, count);
     }
@@ -60,16 +142,52 @@ impl Storage {
         env.storage().instance().get(&COUNTER_KEY).unwrap()
     }
 
-    pub fn set_certificate_owner(env: &Env, certificate_id: &u32, owner: &Address) {
+    pub fn set_ttl_config(env: &Env, config: &TtlConfig) {
+        env.storage().instance().set(&TTL_KEY, config);
+    }
+
+    pub fn get_ttl_config(env: &Env) -> TtlConfig {
+        env.storage().instance().get(&TTL_KEY).unwrap_or(TtlConfig {
+            threshold: CERTIFICATE_LIFETIME_THRESHOLD,
+            extend_to: CERTIFICATE_BUMP_AMOUNT,
+        })
+    }
+
+    // Extends the TTL of an existing persistent entry according to the TTL config
+    pub fn extend_persistent(env: &Env, key: &DataKey) {
+        let config = Self::get_ttl_config(env);
         env.storage()
-            .instance()
-            .set(&DataKey::CertificateOwner(*certificate_id), owner);
+            .persistent()
+            .extend_ttl(key, config.threshold, config.extend_to);
     }
 
-    pub fn get_certificate_owner(env: &Env, certificate_id: &u32) -> Option<Address> {
+    // Extends the TTL of the contract instance according to the TTL config
+    pub fn extend_instance(env: &Env) {
+        let config = Self::get_ttl_config(env);
         env.storage()
             .instance()
-            .get(&DataKey::CertificateOwner(*certificate_id))
+            .extend_ttl(config.threshold, config.extend_to);
+    }
+
+    fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        Self::extend_persistent(env, key);
+    }
+
+    fn get_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+        let value = env.storage().persistent().get(key);
+        if value.is_some() {
+            Self::extend_persistent(env, key);
+        }
+        value
+    }
+
+    pub fn set_certificate_owner(env: &Env, certificate_id: &u32, owner: &Address) {
+        Self::set_persistent(env, &DataKey::CertificateOwner(*certificate_id), owner);
+    }
+
+    pub fn get_certificate_owner(env: &Env, certificate_id: &u32) -> Option<Address> {
+        Self::get_persistent(env, &DataKey::CertificateOwner(*certificate_id))
     }
 
     pub fn set_certificate_metadata(
@@ -77,35 +195,400 @@ impl Storage {
         certificate_id: &u32,
         metadata: &CertificateMetadata,
     ) {
-        env.storage()
-            .instance()
-            .set(&DataKey::CertificateMetadata(*certificate_id), metadata);
+        Self::set_persistent(
+            env,
+            &DataKey::CertificateMetadata(*certificate_id),
+            metadata,
+        );
     }
 
     pub fn get_certificate_metadata(
         env: &Env,
         certificate_id: &u32,
     ) -> Option<CertificateMetadata> {
-        env.storage()
-            .instance()
-            .get(&DataKey::CertificateMetadata(*certificate_id))
+        Self::get_persistent(env, &DataKey::CertificateMetadata(*certificate_id))
     }
 
-    pub fn register_new_certificate(env: &Env, address: &Address) {
-        let count: u32 = env
-            .storage()
-            .instance()
-            .get(&DataKey::CerticateRegister(address.clone()))
-            .unwrap_or(0);
+    // Claims are only stored for certificates that have some
+    pub fn set_certificate_claims(
+        env: &Env,
+        certificate_id: &u32,
+        claims: &Map<Symbol, ClaimValue>,
+    ) {
+        if !claims.is_empty() {
+            Self::set_persistent(env, &DataKey::CertificateClaims(*certificate_id), claims);
+        }
+    }
+
+    pub fn get_certificate_claims(env: &Env, certificate_id: &u32) -> Map<Symbol, ClaimValue> {
+        Self::get_persistent(env, &DataKey::CertificateClaims(*certificate_id))
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    // Only certificates signed over a message other than the concatenated one
+    // record their message version
+    pub fn set_message_version(env: &Env, certificate_id: &u32, version: &MessageVersion) {
+        if *version != MessageVersion::Concatenated {
+            Self::set_persistent(
+                env,
+                &DataKey::CertificateMessageVersion(*certificate_id),
+                version,
+            );
+        }
+    }
+
+    pub fn get_message_version(env: &Env, certificate_id: &u32) -> MessageVersion {
+        Self::get_persistent(env, &DataKey::CertificateMessageVersion(*certificate_id))
+            .unwrap_or(MessageVersion::Concatenated)
+    }
+
+    // Appends a certificate to the owner's index and increments their count
+    pub fn register_new_certificate(env: &Env, address: &Address, certificate_id: &u32) {
+        let count = Self::certificates_issued_to_user(env, address);
+        Self::set_persistent(
+            env,
+            &DataKey::OwnedCertificate(address.clone(), count),
+            certificate_id,
+        );
+        Self::set_persistent(
+            env,
+            &DataKey::OwnedCertificateIndex(*certificate_id),
+            &count,
+        );
+        Self::set_persistent(
+            env,
+            &DataKey::CerticateRegister(address.clone()),
+            &(count + 1),
+        );
+    }
+
+    // Removes a certificate from the owner's index by moving their last certificate
+    // into its slot, and decrements their count
+    pub fn unregister_certificate(env: &Env, address: &Address, certificate_id: &u32) {
+        let count = Self::certificates_issued_to_user(env, address);
+        let last_index = count - 1;
+        let index: u32 =
+            Self::get_persistent(env, &DataKey::OwnedCertificateIndex(*certificate_id)).unwrap();
+
+        if index != last_index {
+            let last_id: u32 =
+                Self::get_persistent(env, &DataKey::OwnedCertificate(address.clone(), last_index))
+                    .unwrap();
+            Self::set_persistent(
+                env,
+                &DataKey::OwnedCertificate(address.clone(), index),
+                &last_id,
+            );
+            Self::set_persistent(env, &DataKey::OwnedCertificateIndex(last_id), &index);
+        }
+
         env.storage()
-            .instance()
-            .set(&DataKey::CerticateRegister(address.clone()), &(count + 1));
+            .persistent()
+            .remove(&DataKey::OwnedCertificate(address.clone(), last_index));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::OwnedCertificateIndex(*certificate_id));
+        Self::set_persistent(
+            env,
+            &DataKey::CerticateRegister(address.clone()),
+            &last_index,
+        );
+    }
+
+    pub fn get_owned_certificate(env: &Env, address: &Address, index: &u32) -> Option<u32> {
+        Self::get_persistent(env, &DataKey::OwnedCertificate(address.clone(), *index))
     }
 
     pub fn certificates_issued_to_user(env: &Env, address: &Address) -> u32 {
+        Self::get_persistent(env, &DataKey::CerticateRegister(address.clone())).unwrap_or(0u32)
+    }
+
+    pub fn set_revocation(env: &Env, certificate_id: &u32, record: &RevocationRecord) {
+        Self::set_persistent(
+            env,
+            &DataKey::CertificateRevocation(*certificate_id),
+            record,
+        );
+    }
+
+    pub fn get_revocation(env: &Env, certificate_id: &u32) -> Option<RevocationRecord> {
+        Self::get_persistent(env, &DataKey::CertificateRevocation(*certificate_id))
+    }
+
+    pub fn consume_nonce(env: &Env, issuer: &Issuer, nonce: &u64) {
+        Self::set_persistent(env, &DataKey::IssuerNonce(issuer.clone(), *nonce), &true);
+    }
+
+    pub fn is_nonce_consumed(env: &Env, issuer: &Issuer, nonce: &u64) -> bool {
+        Self::get_persistent::<bool>(env, &DataKey::IssuerNonce(issuer.clone(), *nonce)).is_some()
+    }
+
+    // Extends the TTL of every entry belonging to a certificate, or to its tombstone
+    // once burned
+    pub fn bump_certificate(env: &Env, certificate_id: &u32) {
+        let burn_key = DataKey::CertificateBurn(*certificate_id);
+        if env.storage().persistent().has(&burn_key) {
+            Self::extend_persistent(env, &burn_key);
+        } else {
+            Self::extend_persistent(env, &DataKey::CertificateOwner(*certificate_id));
+            Self::extend_persistent(env, &DataKey::CertificateMetadata(*certificate_id));
+            Self::extend_persistent(env, &DataKey::OwnedCertificateIndex(*certificate_id));
+        }
+
+        for key in [
+            DataKey::CertificateRevocation(*certificate_id),
+            DataKey::LegacyCertificate(*certificate_id),
+            DataKey::PreviousCertificate(*certificate_id),
+            DataKey::NextCertificate(*certificate_id),
+            DataKey::CertificateClaims(*certificate_id),
+            DataKey::CertificateMessageVersion(*certificate_id),
+        ] {
+            if env.storage().persistent().has(&key) {
+                Self::extend_persistent(env, &key);
+            }
+        }
+    }
+
+    // Marks a certificate migrated from schema version 1, whose signature covers
+    // the version 1 message
+    pub fn set_legacy_certificate(env: &Env, certificate_id: &u32) {
+        Self::set_persistent(env, &DataKey::LegacyCertificate(*certificate_id), &true);
+    }
+
+    pub fn is_legacy_certificate(env: &Env, certificate_id: &u32) -> bool {
+        Self::get_persistent::<bool>(env, &DataKey::LegacyCertificate(*certificate_id)).is_some()
+    }
+
+    // Replaces the owner and metadata of a certificate with its tombstone. The owner
+    // index must be updated separately.
+    pub fn burn_certificate(env: &Env, certificate_id: &u32, record: &BurnRecord) {
+        let persistent = env.storage().persistent();
+        persistent.remove(&DataKey::CertificateOwner(*certificate_id));
+        persistent.remove(&DataKey::CertificateMetadata(*certificate_id));
+        persistent.remove(&DataKey::LegacyCertificate(*certificate_id));
+        persistent.remove(&DataKey::CertificateClaims(*certificate_id));
+        persistent.remove(&DataKey::CertificateMessageVersion(*certificate_id));
+
+        Self::set_persistent(env, &DataKey::CertificateBurn(*certificate_id), record);
+    }
+
+    pub fn get_burn_record(env: &Env, certificate_id: &u32) -> Option<BurnRecord> {
+        Self::get_persistent(env, &DataKey::CertificateBurn(*certificate_id))
+    }
+
+    // Links a reissued certificate to the certificate it supersedes, in both directions
+    pub fn link_reissue(env: &Env, previous_id: &u32, certificate_id: &u32) {
+        Self::set_persistent(env, &DataKey::NextCertificate(*previous_id), certificate_id);
+        Self::set_persistent(
+            env,
+            &DataKey::PreviousCertificate(*certificate_id),
+            previous_id,
+        );
+    }
+
+    pub fn get_previous_certificate(env: &Env, certificate_id: &u32) -> Option<u32> {
+        Self::get_persistent(env, &DataKey::PreviousCertificate(*certificate_id))
+    }
+
+    pub fn get_next_certificate(env: &Env, certificate_id: &u32) -> Option<u32> {
+        Self::get_persistent(env, &DataKey::NextCertificate(*certificate_id))
+    }
+
+    // Approvals are kept in temporary storage and expire at their `live_until_ledger`
+    pub fn set_approval(env: &Env, certificate_id: &u32, approval: &ApprovalData) {
+        let key = DataKey::Approval(*certificate_id);
+        let live_for = approval.live_until_ledger - env.ledger().sequence();
+        env.storage().temporary().set(&key, approval);
         env.storage()
-            .instance()
-            .get(&DataKey::CerticateRegister(address.clone()))
+            .temporary()
+            .extend_ttl(&key, live_for, live_for);
+    }
+
+    pub fn get_approval(env: &Env, certificate_id: &u32) -> Option<ApprovalData> {
+        env.storage()
+            .temporary()
+            .get::<_, ApprovalData>(&DataKey::Approval(*certificate_id))
+            .filter(|approval| approval.live_until_ledger >= env.ledger().sequence())
+    }
+
+    pub fn remove_approval(env: &Env, certificate_id: &u32) {
+        env.storage()
+            .temporary()
+            .remove(&DataKey::Approval(*certificate_id));
+    }
+
+    pub fn set_approval_for_all(
+        env: &Env,
+        owner: &Address,
+        operator: &Address,
+        live_until_ledger: &u32,
+    ) {
+        let key = DataKey::ApprovalForAll(owner.clone(), operator.clone());
+        let live_for = live_until_ledger - env.ledger().sequence();
+        env.storage().temporary().set(&key, live_until_ledger);
+        env.storage()
+            .temporary()
+            .extend_ttl(&key, live_for, live_for);
+    }
+
+    pub fn is_approved_for_all(env: &Env, owner: &Address, operator: &Address) -> bool {
+        env.storage()
+            .temporary()
+            .get::<_, u32>(&DataKey::ApprovalForAll(owner.clone(), operator.clone()))
+            .is_some_and(|live_until_ledger| live_until_ledger >= env.ledger().sequence())
+    }
+
+    pub fn remove_approval_for_all(env: &Env, owner: &Address, operator: &Address) {
+        env.storage()
+            .temporary()
+            .remove(&DataKey::ApprovalForAll(owner.clone(), operator.clone()));
+    }
+
+    pub fn set_pending_recovery(env: &Env, old_owner: &Address, request: &RecoveryRequest) {
+        Self::set_persistent(env, &DataKey::PendingRecovery(old_owner.clone()), request);
+    }
+
+    pub fn get_pending_recovery(env: &Env, old_owner: &Address) -> Option<RecoveryRequest> {
+        Self::get_persistent(env, &DataKey::PendingRecovery(old_owner.clone()))
+    }
+
+    pub fn clear_pending_recovery(env: &Env, old_owner: &Address) {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingRecovery(old_owner.clone()));
+    }
+
+    // Appends a certificate to the course index and to the recipient's completions
+    // of the course
+    pub fn register_course_certificate(
+        env: &Env,
+        course_id: &String,
+        recipient: &Address,
+        certificate_id: &u32,
+    ) {
+        let count = Self::get_course_certificate_count(env, course_id);
+        Self::set_persistent(
+            env,
+            &DataKey::CourseCertificate(course_id.clone(), count),
+            certificate_id,
+        );
+        Self::set_persistent(
+            env,
+            &DataKey::CourseCertificateCount(course_id.clone()),
+            &(count + 1),
+        );
+
+        let completions = Self::get_course_completion_count(env, recipient, course_id);
+        Self::set_persistent(
+            env,
+            &DataKey::CourseCompletion(recipient.clone(), course_id.clone(), completions),
+            certificate_id,
+        );
+        Self::set_persistent(
+            env,
+            &DataKey::CourseCompletionCount(recipient.clone(), course_id.clone()),
+            &(completions + 1),
+        );
+    }
+
+    pub fn get_course_certificate_count(env: &Env, course_id: &String) -> u32 {
+        Self::get_persistent(env, &DataKey::CourseCertificateCount(course_id.clone()))
             .unwrap_or(0u32)
     }
+
+    pub fn get_course_certificate(env: &Env, course_id: &String, index: &u32) -> Option<u32> {
+        Self::get_persistent(env, &DataKey::CourseCertificate(course_id.clone(), *index))
+    }
+
+    pub fn get_course_completion_count(env: &Env, recipient: &Address, course_id: &String) -> u32 {
+        Self::get_persistent(
+            env,
+            &DataKey::CourseCompletionCount(recipient.clone(), course_id.clone()),
+        )
+        .unwrap_or(0u32)
+    }
+
+    pub fn get_course_completion(
+        env: &Env,
+        recipient: &Address,
+        course_id: &String,
+        index: &u32,
+    ) -> Option<u32> {
+        Self::get_persistent(
+            env,
+            &DataKey::CourseCompletion(recipient.clone(), course_id.clone(), *index),
+        )
+    }
+
+    // Maps a key to the issuer identity it belongs or belonged to
+    pub fn set_issuer_id(env: &Env, key: &Issuer, issuer_id: &u32) {
+        Self::set_persistent(env, &DataKey::IssuerIdentity(key.clone()), issuer_id);
+    }
+
+    pub fn get_issuer_id(env: &Env, key: &Issuer) -> Option<u32> {
+        Self::get_persistent(env, &DataKey::IssuerIdentity(key.clone()))
+    }
+
+    pub fn set_issuer_keys(env: &Env, issuer_id: &u32, keys: &Vec<IssuerKey>) {
+        Self::set_persistent(env, &DataKey::IssuerKeys(*issuer_id), keys);
+    }
+
+    pub fn get_issuer_keys(env: &Env, issuer_id: &u32) -> Vec<IssuerKey> {
+        Self::get_persistent(env, &DataKey::IssuerKeys(*issuer_id)).unwrap_or(Vec::new(env))
+    }
+
+    pub fn set_issuer_profile(env: &Env, issuer_id: &u32, profile: &IssuerProfile) {
+        Self::set_persistent(env, &DataKey::IssuerProfile(*issuer_id), profile);
+    }
+
+    pub fn get_issuer_profile(env: &Env, issuer_id: &u32) -> Option<IssuerProfile> {
+        Self::get_persistent(env, &DataKey::IssuerProfile(*issuer_id))
+    }
+
+    // Removes the profile of an issuer along with its quota usage
+    pub fn remove_issuer_profile(env: &Env, issuer_id: &u32) {
+        let persistent = env.storage().persistent();
+        persistent.remove(&DataKey::IssuerProfile(*issuer_id));
+        persistent.remove(&DataKey::IssuerMintWindow(*issuer_id));
+    }
+
+    pub fn set_mint_window(env: &Env, issuer_id: &u32, window: &MintWindow) {
+        Self::set_persistent(env, &DataKey::IssuerMintWindow(*issuer_id), window);
+    }
+
+    pub fn get_mint_window(env: &Env, issuer_id: &u32) -> Option<MintWindow> {
+        Self::get_persistent(env, &DataKey::IssuerMintWindow(*issuer_id))
+    }
+
+    // Removes and returns the issuer keys of a version 1 contract
+    pub fn take_legacy_issuers(env: &Env) -> Vec<BytesN<65>> {
+        let issuers = env
+            .storage()
+            .instance()
+            .get(&ISSUERS_KEY)
+            .unwrap_or(Vec::new(env));
+        env.storage().instance().remove(&ISSUERS_KEY);
+        issuers
+    }
+
+    // Removes and returns the owner and metadata of a certificate of a version 1
+    // contract, along with the owner's certificate count
+    pub fn take_legacy_certificate(
+        env: &Env,
+        certificate_id: &u32,
+    ) -> Option<(Address, LegacyCertificateMetadata)> {
+        let instance = env.storage().instance();
+        let owner_key = DataKey::CertificateOwner(*certificate_id);
+        let metadata_key = DataKey::CertificateMetadata(*certificate_id);
+
+        let owner: Address = instance.get(&owner_key)?;
+        let metadata = instance.get(&metadata_key)?;
+
+        instance.remove(&owner_key);
+        instance.remove(&metadata_key);
+        instance.remove(&DataKey::CertificateUri(*certificate_id));
+        instance.remove(&DataKey::CerticateRegister(owner.clone()));
+
+        Some((owner, metadata))
+    }
 }
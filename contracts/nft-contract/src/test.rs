@@ -2,10 +2,25 @@
 extern crate alloc;
 extern crate std;
 
-use super::{contract::*, types::*, utils::*};
-use soroban_sdk::testutils::Address as _;
-use soroban_sdk::testutils::{AuthorizedFunction, AuthorizedInvocation};
-use soroban_sdk::{Address, Env, IntoVal, String, Symbol, Vec};
+use super::{contract::*, errors::Error, types::*, utils::*};
+use k256::ecdsa::SigningKey;
+use soroban_sdk::testutils::storage::Persistent as _;
+use soroban_sdk::testutils::{Address as _, Events, Ledger, Register};
+use soroban_sdk::testutils::{AuthorizedFunction, AuthorizedInvocation, MockAuth, MockAuthInvoke};
+use soroban_sdk::{
+    symbol_short, vec, Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, Val, Vec,
+};
+
+// Built by `make build`, which `make test` runs first
+const CONTRACT_WASM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../target/wasm32-unknown-unknown/release/nft_contract.wasm"
+);
+
+// The contract as released before schema versioning, built from the baseline commit
+mod v1 {
+    soroban_sdk::contractimport!(file = "fixtures/nft_contract_v1.wasm");
+}
 
 struct TestContext {
     nft_address: Address,
@@ -14,8 +29,115 @@ struct TestContext {
     client: CertificateNFTClient<'static>,
 }
 
+fn issuer_profile(env: &Env) -> IssuerProfile {
+    IssuerProfile {
+        name: String::from_str(env, "Tradoxus Academy"),
+        website: String::from_str(env, "https://tradoxus.com"),
+        metadata_uri: String::from_str(
+            env,
+            "ipfs://QmYwAPJzv5CZsnAzt8auVZRn1pU7fcWwaFcDbpuYuXp1tc",
+        ),
+        course_scopes: Vec::new(env),
+        max_mints: None,
+        mint_period: 0,
+        active: true,
+    }
+}
+
+/// Metadata document the test certificates commit to
+fn sample_document(env: &Env) -> Bytes {
+    Bytes::from_slice(env, br#"{"name":"Solidity Bootcamp 2025","grade":"A"}"#)
+}
+
+fn document_hash(env: &Env) -> BytesN<32> {
+    env.crypto().sha256(&sample_document(env)).into()
+}
+
+/// Builds the mint request of `metadata`, without claims
+fn request_from(env: &Env, metadata: &CertificateMetadata) -> MintRequest {
+    MintRequest {
+        recipient: metadata.recipient.clone(),
+        course_id: metadata.course_id.clone(),
+        metadata_uri: metadata.metadata_uri.clone(),
+        metadata_hash: metadata.metadata_hash.clone(),
+        issuer: metadata.issuer.clone(),
+        issued_date: metadata.issued_date,
+        valid_from: metadata.valid_from,
+        expires_at: metadata.expires_at,
+        nonce: metadata.nonce,
+        claims: Map::new(env),
+        message_version: MessageVersion::Concatenated,
+        signature: metadata.signature.clone(),
+    }
+}
+
+/// Builds an unsigned mint request for a fresh recipient, as account issuers send
+fn account_request(env: &Env, issuer: &Issuer) -> MintRequest {
+    MintRequest {
+        recipient: Address::generate(env),
+        course_id: String::from_str(env, "course:solidity-bootcamp-2025"),
+        metadata_uri: String::from_str(
+            env,
+            "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
+        ),
+        metadata_hash: document_hash(env),
+        issuer: issuer.clone(),
+        issued_date: env.ledger().timestamp(),
+        valid_from: None,
+        expires_at: None,
+        nonce: 0,
+        claims: Map::new(env),
+        message_version: MessageVersion::Concatenated,
+        signature: Bytes::new(env),
+    }
+}
+
+/// Reads the compiled contract. Tests using it are ignored by a bare `cargo test`,
+/// `make test` builds the contract and runs them.
+fn contract_wasm() -> std::vec::Vec<u8> {
+    std::fs::read(CONTRACT_WASM)
+        .unwrap_or_else(|_| panic!("{CONTRACT_WASM} not found, run `make build` first"))
+}
+
+fn hex_array<const N: usize>(hex: &str) -> [u8; N] {
+    hex::decode(hex).unwrap().try_into().unwrap()
+}
+
+fn hex_bytes(env: &Env, hex: &str) -> Bytes {
+    Bytes::from_slice(env, &hex::decode(hex).unwrap())
+}
+
 impl TestContext {
+    /// Asserts the last event of the last invocation was published by the contract
+    /// with `topics` and `data`
+    fn assert_last_event(&self, topics: Vec<Val>, data: Val) {
+        let events = self.env.events().all();
+        assert_eq!(
+            events.slice(events.len() - 1..),
+            vec![&self.env, (self.nft_address.clone(), topics, data)]
+        );
+    }
+
+    /// Registers `issuer` with an active, unrestricted profile
+    fn add_issuer(&self, issuer: &Issuer) {
+        self.client
+            .add_issuer(&self.admin, issuer, &issuer_profile(&self.env));
+    }
+
     fn new() -> Self {
+        Self::with_policy(TransferPolicy::Transferable)
+    }
+
+    fn with_policy(transfer_policy: TransferPolicy) -> Self {
+        Self::deploy(CertificateNFT, transfer_policy)
+    }
+
+    /// Deploys the compiled contract, so that Wasm execution is metered too
+    fn wasm() -> Self {
+        Self::deploy(contract_wasm().as_slice(), TransferPolicy::Transferable)
+    }
+
+    fn deploy(contract: impl Register, transfer_policy: TransferPolicy) -> Self {
         let env = Env::default();
         env.cost_estimate().budget().reset_unlimited();
         env.mock_all_auths();
@@ -25,10 +147,10 @@ impl TestContext {
         let name = String::from_str(&env, "Traxodus Cerficates").to_val();
         let symbol = String::from_str(&env, "TxCerts").to_val();
 
-        let nft_address = env.register(CertificateNFT, (&name, &symbol));
+        let nft_address = env.register(contract, (&name, &symbol));
         let client = CertificateNFTClient::new(&env, &nft_address);
 
-        client.initialize(&admin);
+        client.initialize(&admin, &transfer_policy);
 
         TestContext {
             nft_address,
@@ -37,13 +159,190 @@ impl TestContext {
             client,
         }
     }
+
+    /// Registers a fresh issuer and mints a certificate signed by it
+    fn mint(&self) -> (u32, CertificateMetadata, SigningKey) {
+        self.mint_with_window(None, None)
+    }
+
+    fn mint_with_window(
+        &self,
+        valid_from: Option<u64>,
+        expires_at: Option<u64>,
+    ) -> (u32, CertificateMetadata, SigningKey) {
+        self.mint_to(&Address::generate(&self.env), valid_from, expires_at)
+    }
+
+    fn mint_to(
+        &self,
+        recipient: &Address,
+        valid_from: Option<u64>,
+        expires_at: Option<u64>,
+    ) -> (u32, CertificateMetadata, SigningKey) {
+        let empty_bytes = gen_random_bytes::<65>(&self.env);
+
+        let (sk, issuer) = generate_issuer(&self.env);
+
+        self.add_issuer(&issuer);
+
+        let mut metadata = CertificateMetadata {
+            course_id: String::from_str(&self.env, "course:solidity-bootcamp-2025"),
+            metadata_hash: document_hash(&self.env),
+            issued_date: self.env.ledger().timestamp(),
+            valid_from,
+            expires_at,
+            nonce: 0,
+            recipient: recipient.clone(),
+            metadata_uri: String::from_str(
+                &self.env,
+                "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
+            ),
+            signature: empty_bytes.into(),
+            issuer,
+        };
+
+        metadata.signature = sign(&self.env, metadata.clone(), sk.clone()).into();
+
+        let certificate_id = self
+            .client
+            .mint_certificate(&request_from(&self.env, &metadata));
+
+        (certificate_id, metadata, sk)
+    }
+
+    /// Mints a certificate for a fresh recipient, signing the certificate message
+    /// with `sign`
+    fn mint_signed(
+        &self,
+        issuer: &Issuer,
+        sign: impl Fn(&Bytes) -> Bytes,
+    ) -> (u32, CertificateMetadata) {
+        self.add_issuer(issuer);
+
+        let mut metadata = CertificateMetadata {
+            course_id: String::from_str(&self.env, "course:solidity-bootcamp-2025"),
+            metadata_hash: document_hash(&self.env),
+            issued_date: self.env.ledger().timestamp(),
+            valid_from: None,
+            expires_at: None,
+            nonce: 0,
+            recipient: Address::generate(&self.env),
+            metadata_uri: String::from_str(
+                &self.env,
+                "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
+            ),
+            signature: Bytes::new(&self.env),
+            issuer: issuer.clone(),
+        };
+
+        metadata.signature = sign(&build_metadata_message(
+            &self.env,
+            &metadata,
+            &Map::new(&self.env),
+        ));
+
+        let certificate_id = self
+            .client
+            .mint_certificate(&request_from(&self.env, &metadata));
+
+        (certificate_id, metadata)
+    }
+
+    /// Mints the certificate described by `request`
+    fn mint_from_request(&self, request: &MintRequest) -> u32 {
+        self.client.mint_certificate(request)
+    }
+
+    /// Builds a request reissuing `previous_id` to its owner with a corrected
+    /// metadata URI, signed by `sk`
+    fn reissue_request(
+        &self,
+        sk: &SigningKey,
+        issuer: &Issuer,
+        previous_id: u32,
+        nonce: u64,
+    ) -> MintRequest {
+        let previous = self.client.certificate_metadata(&previous_id);
+
+        let metadata = CertificateMetadata {
+            recipient: previous.owner,
+            metadata_uri: String::from_str(
+                &self.env,
+                "ipfs://QmT5NvUtoM5nWFfrQdVrFtvGfKFmG7AHE8P34isapyhCxX",
+            ),
+            issued_date: self.env.ledger().timestamp(),
+            nonce,
+            issuer: issuer.clone(),
+            ..previous.metadata
+        };
+
+        let message = build_reissue_message(
+            &self.env,
+            &previous_id,
+            &build_metadata_message(&self.env, &metadata, &Map::new(&self.env)),
+        );
+
+        MintRequest {
+            signature: sign_message(&self.env, &message, sk.clone()).into(),
+            ..request_from(&self.env, &metadata)
+        }
+    }
+
+    /// Builds the typed message of `request` for this contract, as an off-chain
+    /// signer would, bound to `previous_id` when reissuing
+    fn typed_message(&self, request: &MintRequest, previous_id: Option<u32>) -> Bytes {
+        let metadata = CertificateMetadata {
+            course_id: request.course_id.clone(),
+            issued_date: request.issued_date,
+            valid_from: request.valid_from,
+            expires_at: request.expires_at,
+            nonce: request.nonce,
+            issuer: request.issuer.clone(),
+            metadata_uri: request.metadata_uri.clone(),
+            metadata_hash: request.metadata_hash.clone(),
+            signature: Bytes::new(&self.env),
+            recipient: request.recipient.clone(),
+        };
+
+        build_typed_certificate_message(
+            &self.env,
+            &self.client.domain_separator(),
+            &metadata,
+            &request.claims,
+            previous_id,
+        )
+    }
+
+    /// Builds a mint request for a fresh recipient signed by `sk`
+    fn mint_request(&self, sk: &SigningKey, issuer: &Issuer, nonce: u64) -> MintRequest {
+        let mut metadata = CertificateMetadata {
+            course_id: String::from_str(&self.env, "course:solidity-bootcamp-2025"),
+            metadata_hash: document_hash(&self.env),
+            issued_date: self.env.ledger().timestamp(),
+            valid_from: None,
+            expires_at: None,
+            nonce,
+            recipient: Address::generate(&self.env),
+            metadata_uri: String::from_str(
+                &self.env,
+                "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
+            ),
+            signature: gen_random_bytes::<65>(&self.env).into(),
+            issuer: issuer.clone(),
+        };
+
+        metadata.signature = sign(&self.env, metadata.clone(), sk.clone()).into();
+
+        request_from(&self.env, &metadata)
+    }
 }
 
 #[test]
 #[should_panic(expected = "#100")]
 fn init_only_once() {
     let ctx = TestContext::new();
-    ctx.client.initialize(&ctx.admin);
+    ctx.client
+        .initialize(&ctx.admin, &TransferPolicy::Transferable);
 }
 
 #[test]
@@ -149,18 +448,19 @@ fn test_claim_admin_fail() {
 fn test_admin_add_issuer() {
     let ctx = TestContext::new();
 
-    let new_issuer = gen_random_bytes::<65>(&ctx.env);
-    ctx.client.add_issuer(&new_issuer);
+    let new_issuer = Issuer::Secp256k1(gen_random_bytes::<65>(&ctx.env));
+    let profile = issuer_profile(&ctx.env);
+    ctx.client.add_issuer(&ctx.admin, &new_issuer, &profile);
 
     assert_eq!(
         ctx.env.auths(),
         std::vec![(
-            ctx.admin,
+            ctx.admin.clone(),
             AuthorizedInvocation {
                 function: AuthorizedFunction::Contract((
                     ctx.client.address.clone(),
                     Symbol::new(&ctx.env, "add_issuer"),
-                    (new_issuer.clone(),).into_val(&ctx.env)
+                    (ctx.admin.clone(), new_issuer.clone(), profile).into_val(&ctx.env)
                 )),
                 sub_invocations: std::vec![]
             }
@@ -174,8 +474,9 @@ fn test_admin_add_issuer_fail() {
     let ctx = TestContext::new();
 
     let fake_admin = Address::generate(&ctx.env);
-    let new_issuer = gen_random_bytes::<65>(&ctx.env);
-    ctx.client.add_issuer(&new_issuer);
+    let new_issuer = Issuer::Secp256k1(gen_random_bytes::<65>(&ctx.env));
+    let profile = issuer_profile(&ctx.env);
+    ctx.client.add_issuer(&ctx.admin, &new_issuer, &profile);
 
     assert_eq!(
         ctx.env.auths(),
@@ -185,7 +486,7 @@ fn test_admin_add_issuer_fail() {
                 function: AuthorizedFunction::Contract((
                     ctx.client.address.clone(),
                     Symbol::new(&ctx.env, "add_issuer"),
-                    (new_issuer.clone(),).into_val(&ctx.env)
+                    (ctx.admin.clone(), new_issuer.clone(), profile).into_val(&ctx.env)
                 )),
                 sub_invocations: std::vec![]
             }
@@ -197,28 +498,28 @@ fn test_admin_add_issuer_fail() {
 #[should_panic(expected = "#106")]
 fn test_add_issuer_fails_if_already_exists() {
     let ctx = TestContext::new();
-    let new_issuer = gen_random_bytes::<65>(&ctx.env);
-    ctx.client.add_issuer(&new_issuer);
-    ctx.client.add_issuer(&new_issuer);
+    let new_issuer = Issuer::Secp256k1(gen_random_bytes::<65>(&ctx.env));
+    ctx.add_issuer(&new_issuer);
+    ctx.add_issuer(&new_issuer);
 }
 
 #[test]
 fn test_admin_remove_issuer() {
     let ctx = TestContext::new();
 
-    let new_issuer = gen_random_bytes::<65>(&ctx.env);
-    ctx.client.add_issuer(&new_issuer);
-    ctx.client.remove_issuer(&new_issuer);
+    let new_issuer = Issuer::Secp256k1(gen_random_bytes::<65>(&ctx.env));
+    ctx.add_issuer(&new_issuer);
+    ctx.client.remove_issuer(&ctx.admin, &new_issuer);
 
     assert_eq!(
         ctx.env.auths(),
         std::vec![(
-            ctx.admin,
+            ctx.admin.clone(),
             AuthorizedInvocation {
                 function: AuthorizedFunction::Contract((
                     ctx.client.address.clone(),
                     Symbol::new(&ctx.env, "remove_issuer"),
-                    (new_issuer.clone(),).into_val(&ctx.env)
+                    (ctx.admin.clone(), new_issuer.clone()).into_val(&ctx.env)
                 )),
                 sub_invocations: std::vec![]
             }
@@ -232,9 +533,9 @@ fn test_admin_remove_issuer_fail() {
     let ctx = TestContext::new();
 
     let fake_admin = Address::generate(&ctx.env);
-    let new_issuer = gen_random_bytes::<65>(&ctx.env);
-    ctx.client.add_issuer(&new_issuer);
-    ctx.client.remove_issuer(&new_issuer);
+    let new_issuer = Issuer::Secp256k1(gen_random_bytes::<65>(&ctx.env));
+    ctx.add_issuer(&new_issuer);
+    ctx.client.remove_issuer(&ctx.admin, &new_issuer);
 
     assert_eq!(
         ctx.env.auths(),
@@ -244,7 +545,7 @@ fn test_admin_remove_issuer_fail() {
                 function: AuthorizedFunction::Contract((
                     ctx.client.address.clone(),
                     Symbol::new(&ctx.env, "remove_issuer"),
-                    (new_issuer.clone(),).into_val(&ctx.env)
+                    (ctx.admin.clone(), new_issuer.clone()).into_val(&ctx.env)
                 )),
                 sub_invocations: std::vec![]
             }
@@ -256,8 +557,8 @@ fn test_admin_remove_issuer_fail() {
 #[should_panic(expected = "#107")]
 fn test_add_issuer_fails_if_not_found() {
     let ctx = TestContext::new();
-    let rand_issuer = gen_random_bytes::<65>(&ctx.env);
-    ctx.client.remove_issuer(&rand_issuer);
+    let rand_issuer = Issuer::Secp256k1(gen_random_bytes::<65>(&ctx.env));
+    ctx.client.remove_issuer(&ctx.admin, &rand_issuer);
 }
 
 #[test]
@@ -269,14 +570,18 @@ fn test_signature() {
 
     let metadata = CertificateMetadata {
         course_id: String::from_str(&ctx.env, "course:solidity-bootcamp-2025"),
+        metadata_hash: document_hash(&ctx.env),
         issued_date: ctx.env.ledger().timestamp(),
+        valid_from: None,
+        expires_at: None,
+        nonce: 0,
         recipient: Address::generate(&ctx.env),
         metadata_uri: String::from_str(
             &ctx.env,
             "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
         ),
-        signature: empty_bytes,
-        issuer: pk.clone(),
+        signature: empty_bytes.into(),
+        issuer: Issuer::Secp256k1(pk.clone()),
     };
 
     let signature = sign(&ctx.env, metadata.clone(), sk);
@@ -286,7 +591,12 @@ fn test_signature() {
         &metadata.recipient,
         &metadata.course_id,
         &metadata.metadata_uri,
+        &metadata.metadata_hash,
         &metadata.issued_date,
+        &metadata.valid_from,
+        &metadata.expires_at,
+        &metadata.nonce,
+        &Map::new(&ctx.env),
     );
 
     let valid = verify_issuer_signature(&ctx.env, &pk, &signature, message);
@@ -298,34 +608,33 @@ fn test_mint_certificate() {
     let ctx = TestContext::new();
     let empty_bytes = gen_random_bytes::<65>(&ctx.env);
 
-    let (sk, _, new_issuer) = generate_keypair(&ctx.env);
+    let (sk, new_issuer) = generate_issuer(&ctx.env);
 
-    ctx.client.add_issuer(&new_issuer);
+    ctx.add_issuer(&new_issuer);
 
     let mut metadata = CertificateMetadata {
         course_id: String::from_str(&ctx.env, "course:solidity-bootcamp-2025"),
+        metadata_hash: document_hash(&ctx.env),
         issued_date: ctx.env.ledger().timestamp(),
+        valid_from: None,
+        expires_at: None,
+        nonce: 0,
         recipient: Address::generate(&ctx.env),
         metadata_uri: String::from_str(
             &ctx.env,
             "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
         ),
-        signature: empty_bytes,
+        signature: empty_bytes.into(),
         issuer: new_issuer.clone(),
     };
 
     let signature = sign(&ctx.env, metadata.clone(), sk);
 
-    metadata.signature = signature;
+    metadata.signature = signature.into();
 
-    let certificate_id = ctx.client.mint_certificate(
-        &metadata.recipient,
-        &metadata.course_id,
-        &metadata.metadata_uri,
-        &metadata.issuer,
-        &metadata.issued_date,
-        &metadata.signature,
-    );
+    let certificate_id = ctx
+        .client
+        .mint_certificate(&request_from(&ctx.env, &metadata));
 
     let issued_certificates = ctx.client.issued_certificates();
     let certificate_owner = ctx.client.owner_of(&certificate_id);
@@ -342,36 +651,36 @@ fn test_mint_certificate_invalid_signature() {
     let ctx = TestContext::new();
     let empty_bytes = gen_random_bytes::<65>(&ctx.env);
 
-    let (sk, _, new_issuer) = generate_keypair(&ctx.env);
+    let (sk, new_issuer) = generate_issuer(&ctx.env);
 
-    ctx.client.add_issuer(&new_issuer);
+    ctx.add_issuer(&new_issuer);
 
     let wrong_data = String::from_str(&ctx.env, "course:solidity-bootcamp-2030");
 
     let mut metadata = CertificateMetadata {
         course_id: String::from_str(&ctx.env, "course:solidity-bootcamp-2025"),
+        metadata_hash: document_hash(&ctx.env),
         issued_date: ctx.env.ledger().timestamp(),
+        valid_from: None,
+        expires_at: None,
+        nonce: 0,
         recipient: Address::generate(&ctx.env),
         metadata_uri: String::from_str(
             &ctx.env,
             "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
         ),
-        signature: empty_bytes,
+        signature: empty_bytes.into(),
         issuer: new_issuer.clone(),
     };
 
     let signature = sign(&ctx.env, metadata.clone(), sk);
 
-    metadata.signature = signature;
+    metadata.signature = signature.into();
 
-    ctx.client.mint_certificate(
-        &metadata.recipient,
-        &wrong_data,
-        &metadata.metadata_uri,
-        &metadata.issuer,
-        &metadata.issued_date,
-        &metadata.signature,
-    );
+    ctx.client.mint_certificate(&MintRequest {
+        course_id: wrong_data,
+        ..request_from(&ctx.env, &metadata)
+    });
 }
 
 #[test]
@@ -380,32 +689,30 @@ fn test_mint_certificate_failed_not_issuer() {
     let ctx = TestContext::new();
     let empty_bytes = gen_random_bytes::<65>(&ctx.env);
 
-    let (sk, _, new_issuer) = generate_keypair(&ctx.env);
+    let (sk, new_issuer) = generate_issuer(&ctx.env);
 
     let mut metadata = CertificateMetadata {
         course_id: String::from_str(&ctx.env, "course:solidity-bootcamp-2025"),
+        metadata_hash: document_hash(&ctx.env),
         issued_date: ctx.env.ledger().timestamp(),
+        valid_from: None,
+        expires_at: None,
+        nonce: 0,
         recipient: Address::generate(&ctx.env),
         metadata_uri: String::from_str(
             &ctx.env,
             "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
         ),
-        signature: empty_bytes,
+        signature: empty_bytes.into(),
         issuer: new_issuer.clone(),
     };
 
     let signature = sign(&ctx.env, metadata.clone(), sk);
 
-    metadata.signature = signature;
+    metadata.signature = signature.into();
 
-    ctx.client.mint_certificate(
-        &metadata.recipient,
-        &metadata.course_id,
-        &metadata.metadata_uri,
-        &metadata.issuer,
-        &metadata.issued_date,
-        &metadata.signature,
-    );
+    ctx.client
+        .mint_certificate(&request_from(&ctx.env, &metadata));
 }
 
 #[test]
@@ -413,129 +720,3076 @@ fn test_verify_certificate() {
     let ctx = TestContext::new();
     let empty_bytes = gen_random_bytes::<65>(&ctx.env);
 
-    let (sk, _, new_issuer) = generate_keypair(&ctx.env);
+    let (sk, new_issuer) = generate_issuer(&ctx.env);
 
-    ctx.client.add_issuer(&new_issuer);
+    ctx.add_issuer(&new_issuer);
 
     let mut metadata = CertificateMetadata {
         course_id: String::from_str(&ctx.env, "course:solidity-bootcamp-2025"),
+        metadata_hash: document_hash(&ctx.env),
         issued_date: ctx.env.ledger().timestamp(),
+        valid_from: None,
+        expires_at: None,
+        nonce: 0,
         recipient: Address::generate(&ctx.env),
         metadata_uri: String::from_str(
             &ctx.env,
             "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
         ),
-        signature: empty_bytes,
+        signature: empty_bytes.into(),
         issuer: new_issuer.clone(),
     };
 
     let signature = sign(&ctx.env, metadata.clone(), sk);
 
-    metadata.signature = signature;
+    metadata.signature = signature.into();
 
-    let certificate_id = ctx.client.mint_certificate(
-        &metadata.recipient,
-        &metadata.course_id,
-        &metadata.metadata_uri,
-        &metadata.issuer,
-        &metadata.issued_date,
-        &metadata.signature,
-    );
+    let certificate_id = ctx
+        .client
+        .mint_certificate(&request_from(&ctx.env, &metadata));
 
-    let certificate_data = &build_certificate_message(
-        &ctx.env,
-        &metadata.recipient,
-        &metadata.course_id,
-        &metadata.metadata_uri,
-        &metadata.issued_date,
+    let result = ctx.client.verify_certificate(&certificate_id);
+
+    assert!(result.valid);
+    assert!(result.reasons.is_empty());
+}
+
+#[test]
+fn test_verify_certificate_failed() {
+    let ctx = TestContext::new();
+    let (certificate_id, mut metadata, _) = ctx.mint();
+
+    // data that does not match the signature, as if storage had been altered
+    metadata.course_id = String::from_str(&ctx.env, "course:solidity-bootcamp-2030");
+    ctx.env.as_contract(&ctx.nft_address, || {
+        ctx.env
+            .storage()
+            .persistent()
+            .set(&DataKey::CertificateMetadata(certificate_id), &metadata);
+    });
+
+    let result = ctx.client.verify_certificate(&certificate_id);
+
+    assert!(!result.valid);
+    assert_eq!(
+        result.reasons,
+        vec![&ctx.env, VerificationFailure::InvalidSignature]
     );
+}
 
-    let verified = ctx
-        .client
-        .verify_certificate(&certificate_id, certificate_data);
+#[test]
+#[should_panic(expected = "#103")]
+fn test_verify_certificate_failed_certificate_not_found() {
+    let ctx = TestContext::new();
 
-    assert!(verified);
+    ctx.client.verify_certificate(&20);
 }
 
 #[test]
-fn test_verify_certificate_failed() {
+fn test_revoke_certificate_by_admin() {
     let ctx = TestContext::new();
-    let empty_bytes = gen_random_bytes::<65>(&ctx.env);
+    let (certificate_id, _, _) = ctx.mint();
 
-    let (sk, _, new_issuer) = generate_keypair(&ctx.env);
+    let reason = String::from_str(&ctx.env, "issued by mistake");
+    ctx.client
+        .revoke_certificate(&ctx.admin, &certificate_id, &reason);
 
-    ctx.client.add_issuer(&new_issuer);
+    let record = ctx.client.revocation(&certificate_id).unwrap();
+    assert_eq!(record.reason, reason);
+    assert_eq!(record.revoked_by, Revoker::Operator(ctx.admin.clone()));
 
-    let mut metadata = CertificateMetadata {
-        course_id: String::from_str(&ctx.env, "course:solidity-bootcamp-2025"),
-        issued_date: ctx.env.ledger().timestamp(),
-        recipient: Address::generate(&ctx.env),
-        metadata_uri: String::from_str(
-            &ctx.env,
-            "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
-        ),
-        signature: empty_bytes,
-        issuer: new_issuer.clone(),
-    };
+    let detail = ctx.client.certificate_metadata(&certificate_id);
+    assert_eq!(detail.status, CertificateStatus::Revoked);
+}
 
-    let signature = sign(&ctx.env, metadata.clone(), sk);
+#[test]
+fn test_revoke_certificate_by_issuer() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, sk) = ctx.mint();
 
-    metadata.signature = signature;
+    let reason = String::from_str(&ctx.env, "academic fraud");
+    let message = build_revocation_message(&ctx.env, &ctx.nft_address, &certificate_id, &reason);
+    let signature = sign_message(&ctx.env, &message, sk);
 
-    let certificate_id = ctx.client.mint_certificate(
-        &metadata.recipient,
-        &metadata.course_id,
-        &metadata.metadata_uri,
-        &metadata.issuer,
-        &metadata.issued_date,
-        &metadata.signature,
+    ctx.client
+        .issuer_revoke_certificate(&certificate_id, &reason, &signature.into());
+
+    let record = ctx.client.revocation(&certificate_id).unwrap();
+    assert_eq!(record.revoked_by, Revoker::Issuer(metadata.issuer));
+    assert!(ctx.env.auths().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "#105")]
+fn test_revoke_certificate_signed_for_other_deployment() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, sk) = ctx.mint();
+
+    // the same issuer key and certificate ID on another contract
+    let reason = String::from_str(&ctx.env, "academic fraud");
+    let other_contract = Address::generate(&ctx.env);
+    let message = build_revocation_message(&ctx.env, &other_contract, &certificate_id, &reason);
+    let signature = sign_message(&ctx.env, &message, sk);
+
+    ctx.client
+        .issuer_revoke_certificate(&certificate_id, &reason, &signature.into());
+}
+
+#[test]
+#[should_panic(expected = "#105")]
+fn test_revoke_certificate_invalid_signature() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint();
+    let (other_sk, _, _) = generate_keypair(&ctx.env);
+
+    let reason = String::from_str(&ctx.env, "academic fraud");
+    let message = build_revocation_message(&ctx.env, &ctx.nft_address, &certificate_id, &reason);
+    let signature = sign_message(&ctx.env, &message, other_sk);
+
+    ctx.client
+        .issuer_revoke_certificate(&certificate_id, &reason, &signature.into());
+}
+
+#[test]
+#[should_panic(expected = "#110")]
+fn test_revoke_certificate_twice() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint();
+
+    let reason = String::from_str(&ctx.env, "issued by mistake");
+    ctx.client
+        .revoke_certificate(&ctx.admin, &certificate_id, &reason);
+    ctx.client
+        .revoke_certificate(&ctx.admin, &certificate_id, &reason);
+}
+
+#[test]
+fn test_verify_certificate_failed_revoked() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint();
+
+    let reason = String::from_str(&ctx.env, "issued by mistake");
+    ctx.client
+        .revoke_certificate(&ctx.admin, &certificate_id, &reason);
+
+    let result = ctx.client.verify_certificate(&certificate_id);
+
+    assert!(!result.valid);
+    assert_eq!(result.reasons, vec![&ctx.env, VerificationFailure::Revoked]);
+}
+
+#[test]
+fn test_is_valid_at() {
+    let ctx = TestContext::new();
+    ctx.env.ledger().set_timestamp(1_000);
+
+    let (certificate_id, _, _) = ctx.mint_with_window(Some(1_000), Some(2_000));
+
+    assert!(!ctx.client.is_valid_at(&certificate_id, &999));
+    assert!(ctx.client.is_valid_at(&certificate_id, &1_000));
+    assert!(ctx.client.is_valid_at(&certificate_id, &2_000));
+    assert!(!ctx.client.is_valid_at(&certificate_id, &2_001));
+
+    let reason = String::from_str(&ctx.env, "issued by mistake");
+    ctx.client
+        .revoke_certificate(&ctx.admin, &certificate_id, &reason);
+
+    assert!(!ctx.client.is_valid_at(&certificate_id, &1_500));
+}
+
+#[test]
+fn test_certificate_metadata_reports_expiry() {
+    let ctx = TestContext::new();
+    ctx.env.ledger().set_timestamp(1_000);
+
+    let (certificate_id, _, _) = ctx.mint_with_window(Some(1_500), Some(2_000));
+
+    let detail = ctx.client.certificate_metadata(&certificate_id);
+    assert_eq!(detail.status, CertificateStatus::NotYetValid);
+
+    ctx.env.ledger().set_timestamp(1_500);
+    let detail = ctx.client.certificate_metadata(&certificate_id);
+    assert_eq!(detail.status, CertificateStatus::Active);
+
+    ctx.env.ledger().set_timestamp(2_001);
+    let detail = ctx.client.certificate_metadata(&certificate_id);
+    assert_eq!(detail.status, CertificateStatus::Expired);
+}
+
+#[test]
+fn test_verify_certificate_failed_expired() {
+    let ctx = TestContext::new();
+    ctx.env.ledger().set_timestamp(1_000);
+
+    let (certificate_id, _, _) = ctx.mint_with_window(None, Some(2_000));
+
+    assert!(ctx.client.verify_certificate(&certificate_id).valid);
+
+    ctx.env.ledger().set_timestamp(2_001);
+
+    let result = ctx.client.verify_certificate(&certificate_id);
+
+    assert!(!result.valid);
+    assert_eq!(result.reasons, vec![&ctx.env, VerificationFailure::Expired]);
+}
+
+#[test]
+fn test_verify_certificate_failed_not_yet_valid() {
+    let ctx = TestContext::new();
+    ctx.env.ledger().set_timestamp(1_000);
+
+    let (certificate_id, _, _) = ctx.mint_with_window(Some(1_500), None);
+
+    let result = ctx.client.verify_certificate(&certificate_id);
+
+    assert!(!result.valid);
+    assert_eq!(
+        result.reasons,
+        vec![&ctx.env, VerificationFailure::NotYetValid]
     );
+}
 
-    let wrong_data = String::from_str(&ctx.env, "course:solidity-bootcamp-2030");
+#[test]
+fn test_verify_certificate_reports_every_failure() {
+    let ctx = TestContext::new();
+    ctx.env.ledger().set_timestamp(1_000);
 
-    let certificate_data = &build_certificate_message(
-        &ctx.env,
-        &metadata.recipient,
-        &wrong_data,
-        &metadata.metadata_uri,
-        &metadata.issued_date,
+    let (certificate_id, metadata, _) = ctx.mint_with_window(None, Some(2_000));
+
+    let reason = String::from_str(&ctx.env, "issued by mistake");
+    ctx.client
+        .revoke_certificate(&ctx.admin, &certificate_id, &reason);
+    ctx.env.ledger().set_timestamp(2_001);
+    ctx.client.remove_issuer(&ctx.admin, &metadata.issuer);
+
+    let result = ctx.client.verify_certificate(&certificate_id);
+
+    assert!(!result.valid);
+    assert_eq!(
+        result.reasons,
+        vec![
+            &ctx.env,
+            VerificationFailure::Revoked,
+            VerificationFailure::Expired,
+            VerificationFailure::IssuerNotTrusted,
+        ]
     );
+}
+
+#[test]
+#[should_panic(expected = "#109")]
+fn test_mint_certificate_invalid_validity_window() {
+    let ctx = TestContext::new();
+    ctx.mint_with_window(Some(2_000), Some(1_000));
+}
+
+#[test]
+#[should_panic(expected = "#113")]
+fn test_mint_certificate_replayed_signature() {
+    let ctx = TestContext::new();
+    let (_, metadata, _) = ctx.mint();
+
+    ctx.client
+        .mint_certificate(&request_from(&ctx.env, &metadata));
+}
+
+#[test]
+fn test_mint_certificate_with_new_nonce() {
+    let ctx = TestContext::new();
+    let (_, mut metadata, sk) = ctx.mint();
 
-    let verified = ctx
+    assert!(ctx.client.is_nonce_used(&metadata.issuer, &metadata.nonce));
+    assert!(!ctx.client.is_nonce_used(&metadata.issuer, &1));
+
+    metadata.nonce = 1;
+    metadata.signature = sign(&ctx.env, metadata.clone(), sk).into();
+
+    let certificate_id = ctx
         .client
-        .verify_certificate(&certificate_id, certificate_data);
+        .mint_certificate(&request_from(&ctx.env, &metadata));
 
-    assert!(!verified);
+    assert_eq!(certificate_id, 1);
+    assert_eq!(ctx.client.user_issued_certificates(&metadata.recipient), 2);
+    assert!(ctx.client.is_nonce_used(&metadata.issuer, &1));
 }
 
 #[test]
-#[should_panic(expected = "#103")]
-fn test_verify_certificate_failed_certificate_not_found() {
+#[should_panic(expected = "#105")]
+fn test_mint_certificate_nonce_not_signed() {
     let ctx = TestContext::new();
-    let empty_bytes = gen_random_bytes::<65>(&ctx.env);
+    let (_, metadata, _) = ctx.mint();
 
-    let metadata = CertificateMetadata {
-        course_id: String::from_str(&ctx.env, "course:solidity-bootcamp-2025"),
-        issued_date: ctx.env.ledger().timestamp(),
-        recipient: Address::generate(&ctx.env),
-        metadata_uri: String::from_str(
-            &ctx.env,
-            "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
-        ),
-        signature: empty_bytes.clone(),
-        issuer: empty_bytes.clone(),
+    ctx.client.mint_certificate(&MintRequest {
+        nonce: 1,
+        ..request_from(&ctx.env, &metadata)
+    });
+}
+
+#[test]
+fn test_certificate_stored_in_persistent_storage() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, _) = ctx.mint();
+
+    ctx.env.as_contract(&ctx.nft_address, || {
+        let owner_key = DataKey::CertificateOwner(certificate_id);
+        let metadata_key = DataKey::CertificateMetadata(certificate_id);
+        let register_key = DataKey::CerticateRegister(metadata.recipient.clone());
+
+        assert!(!ctx.env.storage().instance().has(&owner_key));
+        assert!(!ctx.env.storage().instance().has(&metadata_key));
+        assert!(!ctx.env.storage().instance().has(&register_key));
+
+        let storage = ctx.env.storage().persistent();
+        assert_eq!(storage.get_ttl(&owner_key), CERTIFICATE_BUMP_AMOUNT);
+        assert_eq!(storage.get_ttl(&metadata_key), CERTIFICATE_BUMP_AMOUNT);
+        assert_eq!(storage.get_ttl(&register_key), CERTIFICATE_BUMP_AMOUNT);
+    });
+}
+
+#[test]
+fn test_bump_certificate() {
+    let ctx = TestContext::new();
+    ctx.client.set_ttl_config(&5_000, &10_000);
+
+    let (certificate_id, _, _) = ctx.mint();
+    let owner_key = DataKey::CertificateOwner(certificate_id);
+
+    let ttl = || {
+        ctx.env.as_contract(&ctx.nft_address, || {
+            ctx.env.storage().persistent().get_ttl(&owner_key)
+        })
     };
+    assert_eq!(ttl(), 10_000);
 
-    let certificate_id = 20;
+    // entries above the threshold are left untouched
+    ctx.env.ledger().with_mut(|li| li.sequence_number += 3_000);
+    ctx.client.bump_certificate(&certificate_id);
+    assert_eq!(ttl(), 7_000);
 
-    let certificate_data = &build_certificate_message(
-        &ctx.env,
-        &metadata.recipient,
-        &metadata.course_id,
-        &metadata.metadata_uri,
-        &metadata.issued_date,
-    );
+    ctx.env.ledger().with_mut(|li| li.sequence_number += 3_000);
+    ctx.client.bump_certificate(&certificate_id);
+    assert_eq!(ttl(), 10_000);
+}
 
-    ctx.client
-        .verify_certificate(&certificate_id, certificate_data);
+#[test]
+#[should_panic(expected = "#103")]
+fn test_bump_certificate_not_found() {
+    let ctx = TestContext::new();
+    ctx.client.bump_certificate(&20);
+}
+
+#[test]
+#[should_panic(expected = "#109")]
+fn test_set_ttl_config_invalid() {
+    let ctx = TestContext::new();
+    ctx.client.set_ttl_config(&5_000, &1_000);
+}
+
+#[test]
+fn test_balance_and_token_uri() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, _) = ctx.mint();
+
+    assert_eq!(ctx.client.balance(&metadata.recipient), 1);
+    assert_eq!(ctx.client.balance(&Address::generate(&ctx.env)), 0);
+    assert_eq!(ctx.client.token_uri(&certificate_id), metadata.metadata_uri);
+}
+
+#[test]
+fn test_transfer() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, _) = ctx.mint();
+    let to = Address::generate(&ctx.env);
+
+    ctx.client
+        .transfer(&metadata.recipient, &to, &certificate_id);
+
+    assert_eq!(
+        ctx.env.auths(),
+        std::vec![(
+            metadata.recipient.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    ctx.client.address.clone(),
+                    Symbol::new(&ctx.env, "transfer"),
+                    (metadata.recipient.clone(), to.clone(), certificate_id).into_val(&ctx.env)
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+
+    assert_eq!(ctx.client.owner_of(&certificate_id), to);
+    assert_eq!(ctx.client.balance(&metadata.recipient), 0);
+    assert_eq!(ctx.client.balance(&to), 1);
+}
+
+#[test]
+#[should_panic(expected = "#114")]
+fn test_transfer_fails_if_not_owner() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint();
+    let from = Address::generate(&ctx.env);
+    let to = Address::generate(&ctx.env);
+
+    ctx.client.transfer(&from, &to, &certificate_id);
+}
+
+#[test]
+fn test_transfer_from_approved() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, _) = ctx.mint();
+    let spender = Address::generate(&ctx.env);
+    let to = Address::generate(&ctx.env);
+
+    ctx.client
+        .approve(&metadata.recipient, &spender, &certificate_id, &1_000);
+    assert_eq!(
+        ctx.client.get_approved(&certificate_id),
+        Some(spender.clone())
+    );
+
+    ctx.client
+        .transfer_from(&spender, &metadata.recipient, &to, &certificate_id);
+
+    assert_eq!(ctx.client.owner_of(&certificate_id), to);
+    assert_eq!(ctx.client.get_approved(&certificate_id), None);
+}
+
+#[test]
+fn test_transfer_from_operator() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, _) = ctx.mint();
+    let operator = Address::generate(&ctx.env);
+    let to = Address::generate(&ctx.env);
+
+    ctx.client
+        .approve_for_all(&metadata.recipient, &operator, &1_000);
+    assert!(ctx
+        .client
+        .is_approved_for_all(&metadata.recipient, &operator));
+
+    ctx.client
+        .transfer_from(&operator, &metadata.recipient, &to, &certificate_id);
+    assert_eq!(ctx.client.owner_of(&certificate_id), to);
+
+    ctx.client
+        .approve_for_all(&metadata.recipient, &operator, &0);
+    assert!(!ctx
+        .client
+        .is_approved_for_all(&metadata.recipient, &operator));
+}
+
+#[test]
+#[should_panic(expected = "#104")]
+fn test_transfer_from_fails_if_not_approved() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, _) = ctx.mint();
+    let spender = Address::generate(&ctx.env);
+    let to = Address::generate(&ctx.env);
+
+    ctx.client
+        .transfer_from(&spender, &metadata.recipient, &to, &certificate_id);
+}
+
+#[test]
+#[should_panic(expected = "#104")]
+fn test_transfer_from_fails_if_approval_expired() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, _) = ctx.mint();
+    let spender = Address::generate(&ctx.env);
+    let to = Address::generate(&ctx.env);
+
+    ctx.client
+        .approve(&metadata.recipient, &spender, &certificate_id, &100);
+
+    ctx.env.ledger().with_mut(|li| li.sequence_number = 101);
+    assert_eq!(ctx.client.get_approved(&certificate_id), None);
+
+    ctx.client
+        .transfer_from(&spender, &metadata.recipient, &to, &certificate_id);
+}
+
+#[test]
+#[should_panic(expected = "#104")]
+fn test_approve_fails_if_not_owner() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint();
+    let approver = Address::generate(&ctx.env);
+    let spender = Address::generate(&ctx.env);
+
+    ctx.client
+        .approve(&approver, &spender, &certificate_id, &1_000);
+}
+
+#[test]
+fn test_transfer_policy() {
+    for policy in [
+        TransferPolicy::Soulbound,
+        TransferPolicy::AdminOnly,
+        TransferPolicy::Transferable,
+    ] {
+        let ctx = TestContext::with_policy(policy);
+        assert_eq!(ctx.client.transfer_policy(), policy);
+    }
+}
+
+#[test]
+#[should_panic(expected = "#115")]
+fn test_soulbound_transfer_fails() {
+    let ctx = TestContext::with_policy(TransferPolicy::Soulbound);
+    let (certificate_id, metadata, _) = ctx.mint();
+    let to = Address::generate(&ctx.env);
+
+    ctx.client
+        .transfer(&metadata.recipient, &to, &certificate_id);
+}
+
+#[test]
+#[should_panic(expected = "#115")]
+fn test_soulbound_admin_transfer_fails() {
+    let ctx = TestContext::with_policy(TransferPolicy::Soulbound);
+    let (certificate_id, metadata, _) = ctx.mint();
+    let to = Address::generate(&ctx.env);
+
+    ctx.client
+        .admin_transfer(&metadata.recipient, &to, &certificate_id);
+}
+
+#[test]
+#[should_panic(expected = "#115")]
+fn test_admin_only_transfer_from_fails() {
+    let ctx = TestContext::with_policy(TransferPolicy::AdminOnly);
+    let (certificate_id, metadata, _) = ctx.mint();
+    let operator = Address::generate(&ctx.env);
+    let to = Address::generate(&ctx.env);
+
+    ctx.client
+        .approve_for_all(&metadata.recipient, &operator, &1_000);
+    ctx.client
+        .transfer_from(&operator, &metadata.recipient, &to, &certificate_id);
+}
+
+#[test]
+fn test_admin_only_admin_transfer() {
+    let ctx = TestContext::with_policy(TransferPolicy::AdminOnly);
+    let (certificate_id, metadata, _) = ctx.mint();
+    let to = Address::generate(&ctx.env);
+
+    ctx.client
+        .admin_transfer(&metadata.recipient, &to, &certificate_id);
+
+    assert_eq!(
+        ctx.env.auths(),
+        std::vec![(
+            ctx.admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    ctx.client.address.clone(),
+                    Symbol::new(&ctx.env, "admin_transfer"),
+                    (metadata.recipient.clone(), to.clone(), certificate_id).into_val(&ctx.env)
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+
+    assert_eq!(ctx.client.owner_of(&certificate_id), to);
+    assert_eq!(ctx.client.balance(&metadata.recipient), 0);
+    assert_eq!(ctx.client.balance(&to), 1);
+}
+
+#[test]
+fn test_transferable_admin_transfer() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, _) = ctx.mint();
+    let to = Address::generate(&ctx.env);
+
+    ctx.client
+        .admin_transfer(&metadata.recipient, &to, &certificate_id);
+
+    assert_eq!(ctx.client.owner_of(&certificate_id), to);
+}
+
+#[test]
+fn test_recover_certificates() {
+    let ctx = TestContext::with_policy(TransferPolicy::AdminOnly);
+    let old_owner = Address::generate(&ctx.env);
+    let new_owner = Address::generate(&ctx.env);
+    let (first_id, _, _) = ctx.mint_to(&old_owner, None, None);
+    let (second_id, _, _) = ctx.mint_to(&old_owner, None, None);
+    let certificate_ids = vec![&ctx.env, first_id, second_id];
+
+    ctx.client
+        .recover_certificates(&old_owner, &new_owner, &certificate_ids);
+
+    let request = ctx.client.pending_recovery(&old_owner).unwrap();
+    assert_eq!(request.new_owner, new_owner);
+    assert_eq!(request.certificate_ids, certificate_ids);
+
+    ctx.client.accept_recovery(&old_owner);
+
+    assert_eq!(
+        ctx.env.auths(),
+        std::vec![(
+            new_owner.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    ctx.client.address.clone(),
+                    Symbol::new(&ctx.env, "accept_recovery"),
+                    (old_owner.clone(),).into_val(&ctx.env)
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+
+    assert_eq!(ctx.client.owner_of(&first_id), new_owner);
+    assert_eq!(ctx.client.owner_of(&second_id), new_owner);
+    assert_eq!(ctx.client.balance(&old_owner), 0);
+    assert_eq!(ctx.client.balance(&new_owner), 2);
+    assert_eq!(ctx.client.pending_recovery(&old_owner), None);
+
+    assert_eq!(
+        ctx.client.tokens_of_owner(&old_owner, &0, &10),
+        Vec::new(&ctx.env)
+    );
+    assert_eq!(
+        ctx.client.tokens_of_owner(&new_owner, &0, &10),
+        certificate_ids
+    );
+}
+
+#[test]
+fn test_accept_recovery_after_delay() {
+    let ctx = TestContext::with_policy(TransferPolicy::AdminOnly);
+    ctx.client.set_recovery_delay(&86_400);
+
+    let old_owner = Address::generate(&ctx.env);
+    let new_owner = Address::generate(&ctx.env);
+    let (certificate_id, _, _) = ctx.mint_to(&old_owner, None, None);
+
+    ctx.client
+        .recover_certificates(&old_owner, &new_owner, &vec![&ctx.env, certificate_id]);
+    assert!(ctx.client.try_accept_recovery(&old_owner).is_err());
+
+    ctx.env.ledger().with_mut(|li| li.timestamp += 86_400);
+    ctx.client.accept_recovery(&old_owner);
+
+    assert_eq!(ctx.client.owner_of(&certificate_id), new_owner);
+}
+
+#[test]
+#[should_panic(expected = "#117")]
+fn test_accept_recovery_fails_if_locked() {
+    let ctx = TestContext::with_policy(TransferPolicy::AdminOnly);
+    ctx.client.set_recovery_delay(&86_400);
+
+    let old_owner = Address::generate(&ctx.env);
+    let new_owner = Address::generate(&ctx.env);
+    let (certificate_id, _, _) = ctx.mint_to(&old_owner, None, None);
+
+    ctx.client
+        .recover_certificates(&old_owner, &new_owner, &vec![&ctx.env, certificate_id]);
+    ctx.client.accept_recovery(&old_owner);
+}
+
+#[test]
+#[should_panic(expected = "#116")]
+fn test_cancel_recovery() {
+    let ctx = TestContext::with_policy(TransferPolicy::AdminOnly);
+    let old_owner = Address::generate(&ctx.env);
+    let new_owner = Address::generate(&ctx.env);
+    let (certificate_id, _, _) = ctx.mint_to(&old_owner, None, None);
+
+    ctx.client
+        .recover_certificates(&old_owner, &new_owner, &vec![&ctx.env, certificate_id]);
+    ctx.client.cancel_recovery(&old_owner);
+
+    assert_eq!(ctx.client.pending_recovery(&old_owner), None);
+
+    ctx.client.accept_recovery(&old_owner);
+}
+
+#[test]
+#[should_panic(expected = "#114")]
+fn test_recover_certificates_fails_if_not_owner() {
+    let ctx = TestContext::with_policy(TransferPolicy::AdminOnly);
+    let (certificate_id, _, _) = ctx.mint();
+    let old_owner = Address::generate(&ctx.env);
+    let new_owner = Address::generate(&ctx.env);
+
+    ctx.client
+        .recover_certificates(&old_owner, &new_owner, &vec![&ctx.env, certificate_id]);
+}
+
+#[test]
+#[should_panic(expected = "#115")]
+fn test_recover_certificates_fails_if_soulbound() {
+    let ctx = TestContext::with_policy(TransferPolicy::Soulbound);
+    let (certificate_id, metadata, _) = ctx.mint();
+    let new_owner = Address::generate(&ctx.env);
+
+    ctx.client.recover_certificates(
+        &metadata.recipient,
+        &new_owner,
+        &vec![&ctx.env, certificate_id],
+    );
+}
+
+#[test]
+fn test_tokens_of_owner() {
+    let ctx = TestContext::new();
+    let owner = Address::generate(&ctx.env);
+    let to = Address::generate(&ctx.env);
+    let (first_id, _, _) = ctx.mint_to(&owner, None, None);
+    let (second_id, _, _) = ctx.mint_to(&owner, None, None);
+    let (third_id, _, _) = ctx.mint_to(&owner, None, None);
+
+    assert_eq!(
+        ctx.client.tokens_of_owner(&owner, &0, &10),
+        vec![&ctx.env, first_id, second_id, third_id]
+    );
+    assert_eq!(
+        ctx.client.tokens_of_owner(&owner, &1, &1),
+        vec![&ctx.env, second_id]
+    );
+    assert_eq!(
+        ctx.client.tokens_of_owner(&owner, &5, &10),
+        Vec::new(&ctx.env)
+    );
+
+    ctx.client.transfer(&owner, &to, &first_id);
+
+    assert_eq!(
+        ctx.client.tokens_of_owner(&owner, &0, &10),
+        vec![&ctx.env, third_id, second_id]
+    );
+    assert_eq!(
+        ctx.client.tokens_of_owner(&to, &0, &10),
+        vec![&ctx.env, first_id]
+    );
+
+    ctx.client.transfer(&to, &owner, &first_id);
+
+    assert_eq!(
+        ctx.client.tokens_of_owner(&owner, &0, &10),
+        vec![&ctx.env, third_id, second_id, first_id]
+    );
+    assert_eq!(ctx.client.tokens_of_owner(&to, &0, &10), Vec::new(&ctx.env));
+}
+
+#[test]
+fn test_certificates_page() {
+    let ctx = TestContext::new();
+    let (first_id, first, _) = ctx.mint();
+    let (second_id, _, _) = ctx.mint();
+    let (third_id, third, _) = ctx.mint();
+
+    let page = ctx.client.certificates(&0, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().metadata, first);
+    assert_eq!(
+        page.get(1).unwrap(),
+        ctx.client.certificate_metadata(&second_id)
+    );
+
+    let page = ctx.client.certificates(&third_id, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().owner, third.recipient);
+
+    assert!(ctx.client.certificates(&(third_id + 1), &10).is_empty());
+    assert_eq!(ctx.client.certificates(&first_id, &0).len(), 0);
+}
+
+#[test]
+fn test_certificates_for_course() {
+    let ctx = TestContext::new();
+    let (first_id, first, _) = ctx.mint();
+    let (second_id, _, _) = ctx.mint();
+    let other_course = String::from_str(&ctx.env, "course:rust-101");
+
+    assert_eq!(
+        ctx.client
+            .certificates_for_course(&first.course_id, &0, &10),
+        vec![&ctx.env, first_id, second_id]
+    );
+    assert_eq!(
+        ctx.client
+            .certificates_for_course(&first.course_id, &1, &10),
+        vec![&ctx.env, second_id]
+    );
+    assert!(ctx
+        .client
+        .certificates_for_course(&other_course, &0, &10)
+        .is_empty());
+
+    assert!(ctx.client.has_completed(&first.recipient, &first.course_id));
+    assert!(!ctx.client.has_completed(&first.recipient, &other_course));
+    assert!(!ctx
+        .client
+        .has_completed(&Address::generate(&ctx.env), &first.course_id));
+
+    let reason = String::from_str(&ctx.env, "academic fraud");
+    ctx.client
+        .revoke_certificate(&ctx.admin, &first_id, &reason);
+
+    assert!(!ctx.client.has_completed(&first.recipient, &first.course_id));
+}
+
+#[test]
+fn test_has_completed_with_earlier_certificate_after_revocation() {
+    let ctx = TestContext::new();
+    let recipient = Address::generate(&ctx.env);
+    let (first_id, metadata, _) = ctx.mint_to(&recipient, None, None);
+    let (second_id, _, _) = ctx.mint_to(&recipient, None, None);
+
+    let reason = String::from_str(&ctx.env, "issued by mistake");
+    ctx.client
+        .revoke_certificate(&ctx.admin, &second_id, &reason);
+
+    assert!(ctx.client.verify_certificate(&first_id).valid);
+    assert!(ctx.client.has_completed(&recipient, &metadata.course_id));
+
+    ctx.client
+        .revoke_certificate(&ctx.admin, &first_id, &reason);
+
+    assert!(!ctx.client.has_completed(&recipient, &metadata.course_id));
+}
+
+#[test]
+#[should_panic(expected = "#118")]
+fn test_unique_completion() {
+    let ctx = TestContext::new();
+    ctx.client.set_unique_completion(&true);
+    assert!(ctx.client.unique_completion());
+
+    let recipient = Address::generate(&ctx.env);
+    ctx.mint_to(&recipient, None, None);
+    ctx.mint_to(&recipient, None, None);
+}
+
+#[test]
+fn test_unique_completion_after_revocation() {
+    let ctx = TestContext::new();
+    ctx.client.set_unique_completion(&true);
+
+    let recipient = Address::generate(&ctx.env);
+    let (certificate_id, metadata, _) = ctx.mint_to(&recipient, None, None);
+
+    let reason = String::from_str(&ctx.env, "issued by mistake");
+    ctx.client
+        .revoke_certificate(&ctx.admin, &certificate_id, &reason);
+
+    let (reissued_id, _, _) = ctx.mint_to(&recipient, None, None);
+
+    assert!(ctx.client.has_completed(&recipient, &metadata.course_id));
+    assert_eq!(
+        ctx.client
+            .certificates_for_course(&metadata.course_id, &0, &10),
+        vec![&ctx.env, certificate_id, reissued_id]
+    );
+}
+
+#[test]
+fn test_batch_mint_certificates() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let requests = vec![
+        &ctx.env,
+        ctx.mint_request(&sk, &issuer, 0),
+        ctx.mint_request(&sk, &issuer, 1),
+        ctx.mint_request(&sk, &issuer, 2),
+    ];
+
+    let results = ctx.client.batch_mint_certificates(&requests, &true);
+
+    assert_eq!(
+        results,
+        vec![
+            &ctx.env,
+            MintResult::Minted(0),
+            MintResult::Minted(1),
+            MintResult::Minted(2)
+        ]
+    );
+    assert_eq!(ctx.client.issued_certificates(), 3);
+
+    for (certificate_id, request) in requests.iter().enumerate() {
+        assert_eq!(
+            ctx.client.owner_of(&(certificate_id as u32)),
+            request.recipient
+        );
+    }
+}
+
+#[test]
+fn test_batch_mint_certificates_atomic_rolls_back() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let mut invalid = ctx.mint_request(&sk, &issuer, 1);
+    invalid.course_id = String::from_str(&ctx.env, "course:solidity-bootcamp-2030");
+
+    let requests = vec![&ctx.env, ctx.mint_request(&sk, &issuer, 0), invalid];
+
+    let result = ctx.client.try_batch_mint_certificates(&requests, &true);
+
+    assert_eq!(result, Err(Ok(Error::InvalidSignature)));
+    assert_eq!(ctx.client.issued_certificates(), 0);
+    assert!(!ctx.client.is_nonce_used(&issuer, &0));
+}
+
+#[test]
+fn test_batch_mint_certificates_best_effort() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let valid = ctx.mint_request(&sk, &issuer, 0);
+    let mut invalid = ctx.mint_request(&sk, &issuer, 1);
+    invalid.course_id = String::from_str(&ctx.env, "course:solidity-bootcamp-2030");
+
+    let requests = vec![&ctx.env, valid.clone(), invalid, valid];
+
+    let results = ctx.client.batch_mint_certificates(&requests, &false);
+
+    assert_eq!(
+        results,
+        vec![
+            &ctx.env,
+            MintResult::Minted(0),
+            MintResult::Failed(Error::InvalidSignature as u32),
+            MintResult::Failed(Error::DuplicateCertificate as u32)
+        ]
+    );
+    assert_eq!(ctx.client.issued_certificates(), 1);
+}
+
+#[test]
+#[should_panic(expected = "#119")]
+fn test_batch_mint_certificates_too_large() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let mut requests = Vec::new(&ctx.env);
+    for nonce in 0..=MAX_BATCH_SIZE {
+        requests.push_back(ctx.mint_request(&sk, &issuer, nonce.into()));
+    }
+
+    ctx.client.batch_mint_certificates(&requests, &true);
+}
+
+#[test]
+#[ignore = "needs the compiled contract, run by `make test`"]
+fn test_batch_mint_certificates_budget() {
+    let ctx = TestContext::wasm();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+
+    // a mint quota adds the issuer's mint window to the written entries
+    let profile = IssuerProfile {
+        max_mints: Some(1000),
+        ..issuer_profile(&ctx.env)
+    };
+    ctx.client.add_issuer(&ctx.admin, &issuer, &profile);
+
+    // Worst case: every certificate for a new course, with claims and a typed message,
+    // so that each one writes all of its entries
+    let mut requests = Vec::new(&ctx.env);
+    for nonce in 0..MAX_BATCH_SIZE {
+        let mut request = ctx.mint_request(&sk, &issuer, nonce.into());
+        request.course_id = String::from_str(&ctx.env, &std::format!("course:{nonce}"));
+        request.claims = Map::from_array(&ctx.env, [(symbol_short!("grade"), ClaimValue::Int(92))]);
+        request.message_version = MessageVersion::Typed;
+
+        let message = ctx.typed_message(&request, None);
+        request.signature = sign_message(&ctx.env, &message, sk.clone()).into();
+        requests.push_back(request);
+    }
+
+    // Measure against the default network limits, which fail the call if exceeded
+    let mut budget = ctx.env.cost_estimate().budget();
+    budget.reset_default();
+
+    ctx.client.batch_mint_certificates(&requests, &true);
+
+    let cpu = budget.cpu_instruction_cost();
+    let memory = budget.memory_bytes_cost();
+    let resources = ctx.env.cost_estimate().resources();
+
+    // To view this log run: `make test -- --no-capture`
+    std::println!(
+        "batch of {} certificates: cpu {} insns ({} per certificate), memory {} bytes, {} entries written",
+        MAX_BATCH_SIZE,
+        cpu,
+        cpu / MAX_BATCH_SIZE as u64,
+        memory,
+        resources.write_entries
+    );
+
+    // Network per-transaction limits
+    assert!(cpu < 100_000_000);
+    assert!(memory < 40 * 1024 * 1024);
+    assert!(resources.write_entries <= 50);
+}
+
+#[test]
+fn test_mint_certificate_account_issuer() {
+    let ctx = TestContext::new();
+    let institution = Address::generate(&ctx.env);
+    let issuer = Issuer::Account(institution.clone());
+    ctx.add_issuer(&issuer);
+
+    let request = account_request(&ctx.env, &issuer);
+
+    let certificate_id = ctx.client.mint_certificate(&request);
+
+    assert_eq!(
+        ctx.env.auths(),
+        std::vec![(
+            institution.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    ctx.client.address.clone(),
+                    Symbol::new(&ctx.env, "mint_certificate"),
+                    (request.clone(),).into_val(&ctx.env)
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+
+    assert_eq!(ctx.client.owner_of(&certificate_id), request.recipient);
+
+    assert!(ctx.client.verify_certificate(&certificate_id).valid);
+}
+
+#[test]
+fn test_batch_mint_certificates_account_issuer() {
+    let ctx = TestContext::new();
+    let institution = Address::generate(&ctx.env);
+    let issuer = Issuer::Account(institution.clone());
+    ctx.add_issuer(&issuer);
+
+    let mut requests = Vec::new(&ctx.env);
+    for nonce in 0..MAX_BATCH_SIZE {
+        let mut request = account_request(&ctx.env, &issuer);
+        request.nonce = nonce.into();
+        requests.push_back(request);
+    }
+
+    // the institution signs the batch invocation once, as a wallet would
+    ctx.env.mock_auths(&[MockAuth {
+        address: &institution,
+        invoke: &MockAuthInvoke {
+            contract: &ctx.nft_address,
+            fn_name: "batch_mint_certificates",
+            args: (requests.clone(), true).into_val(&ctx.env),
+            sub_invokes: &[],
+        },
+    }]);
+
+    let results = ctx.client.batch_mint_certificates(&requests, &true);
+
+    assert_eq!(results.len(), MAX_BATCH_SIZE);
+    for (certificate_id, request) in requests.iter().enumerate() {
+        assert_eq!(
+            results.get(certificate_id as u32),
+            Some(MintResult::Minted(certificate_id as u32))
+        );
+        assert_eq!(
+            ctx.client.owner_of(&(certificate_id as u32)),
+            request.recipient
+        );
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_mint_certificate_account_issuer_requires_auth() {
+    let ctx = TestContext::new();
+    let issuer = Issuer::Account(Address::generate(&ctx.env));
+    ctx.add_issuer(&issuer);
+
+    ctx.env.set_auths(&[]);
+
+    ctx.client
+        .mint_certificate(&account_request(&ctx.env, &issuer));
+}
+
+#[test]
+fn test_revoke_certificate_by_account_issuer() {
+    let ctx = TestContext::new();
+    let institution = Address::generate(&ctx.env);
+    let issuer = Issuer::Account(institution.clone());
+    ctx.add_issuer(&issuer);
+
+    let certificate_id = ctx
+        .client
+        .mint_certificate(&account_request(&ctx.env, &issuer));
+
+    let reason = String::from_str(&ctx.env, "academic fraud");
+    ctx.client
+        .issuer_revoke_certificate(&certificate_id, &reason, &Bytes::new(&ctx.env));
+
+    assert_eq!(ctx.env.auths()[0].0, institution);
+
+    let record = ctx.client.revocation(&certificate_id).unwrap();
+    assert_eq!(record.revoked_by, Revoker::Issuer(issuer));
+}
+
+#[test]
+fn test_mint_certificate_ed25519_issuer() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_ed25519_issuer(&ctx.env);
+
+    let (certificate_id, metadata) =
+        ctx.mint_signed(&issuer, |message| sign_ed25519(&ctx.env, message, &sk));
+
+    assert_eq!(ctx.client.owner_of(&certificate_id), metadata.recipient);
+    assert_eq!(
+        ctx.client
+            .certificate_metadata(&certificate_id)
+            .metadata
+            .issuer,
+        issuer
+    );
+
+    assert!(ctx.client.verify_certificate(&certificate_id).valid);
+}
+
+#[test]
+fn test_verify_signature_ed25519_vector() {
+    let env = Env::default();
+
+    // RFC 8032 section 7.1, test 2
+    let public_key = BytesN::from_array(
+        &env,
+        &[
+            0x3d, 0x40, 0x17, 0xc3, 0xe8, 0x43, 0x89, 0x5a, 0x92, 0xb7, 0x0a, 0xa7, 0x4d, 0x1b,
+            0x7e, 0xbc, 0x9c, 0x98, 0x2c, 0xcf, 0x2e, 0xc4, 0x96, 0x8c, 0xc0, 0xcd, 0x55, 0xf1,
+            0x2a, 0xf4, 0x66, 0x0c,
+        ],
+    );
+    let message = Bytes::from_slice(&env, &[0x72]);
+    let signature = Bytes::from_slice(
+        &env,
+        &[
+            0x92, 0xa0, 0x09, 0xa9, 0xf0, 0xd4, 0xca, 0xb8, 0x72, 0x0e, 0x82, 0x0b, 0x5f, 0x64,
+            0x25, 0x40, 0xa2, 0xb2, 0x7b, 0x54, 0x16, 0x50, 0x3f, 0x8f, 0xb3, 0x76, 0x22, 0x23,
+            0xeb, 0xdb, 0x69, 0xda, 0x08, 0x5a, 0xc1, 0xe4, 0x3e, 0x15, 0x99, 0x6e, 0x45, 0x8f,
+            0x36, 0x13, 0xd0, 0xf1, 0x1d, 0x8c, 0x38, 0x7b, 0x2e, 0xae, 0xb4, 0x30, 0x2a, 0xee,
+            0xb0, 0x0d, 0x29, 0x16, 0x12, 0xbb, 0x0c, 0x00,
+        ],
+    );
+
+    assert!(verify_signature(
+        &env,
+        &Issuer::Ed25519(public_key.clone()),
+        &signature,
+        &message
+    ));
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_verify_signature_ed25519_vector_mismatch() {
+    let env = Env::default();
+
+    // RFC 8032 section 7.1, test 2 key and signature over a different message
+    let public_key = Issuer::Ed25519(BytesN::from_array(
+        &env,
+        &hex_array("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c"),
+    ));
+    let signature = hex_bytes(
+        &env,
+        "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da\
+         085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+    );
+
+    verify_signature(&env, &public_key, &signature, &hex_bytes(&env, "73"));
+}
+
+#[test]
+fn test_verify_signature_secp256k1_vector() {
+    let env = Env::default();
+
+    // web3.js `accounts.sign("Some data", "0x4c0883a6...")`, which signs the keccak256
+    // of the Ethereum-prefixed message with v = 28
+    let public_key = Issuer::Secp256k1(BytesN::from_array(
+        &env,
+        &hex_array(
+            "044e3b81af9c2234cad09d679ce6035ed1392347ce64ce405f5dcd36228a25de6e\
+             47fd35c4215d1edf53e6f83de344615ce719bdb0fd878f6ed76f06dd277956de",
+        ),
+    ));
+    let message = Bytes::from_slice(&env, b"\x19Ethereum Signed Message:\n9Some data");
+    let mut signature = hex_bytes(
+        &env,
+        "b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd\
+         6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c",
+    );
+
+    assert!(verify_signature(&env, &public_key, &signature, &message));
+
+    // the recovery id may also be given as 0 or 1
+    signature.set(64, 1);
+    assert!(verify_signature(&env, &public_key, &signature, &message));
+
+    // a mismatch recovers a different key rather than aborting
+    let other = Bytes::from_slice(&env, b"\x19Ethereum Signed Message:\n9Some date");
+    assert!(!verify_signature(&env, &public_key, &signature, &other));
+}
+
+#[test]
+fn test_verify_signature_secp256r1_vector() {
+    let env = Env::default();
+
+    // RFC 6979 appendix A.2.5, P-256 with SHA-256, message "test"
+    let public_key = Issuer::Secp256r1(BytesN::from_array(
+        &env,
+        &hex_array(
+            "0460fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6\
+             7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299",
+        ),
+    ));
+    let signature = hex_bytes(
+        &env,
+        "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367\
+         019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083",
+    );
+
+    assert!(verify_signature(
+        &env,
+        &public_key,
+        &signature,
+        &Bytes::from_slice(&env, b"test")
+    ));
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_verify_signature_secp256r1_vector_mismatch() {
+    let env = Env::default();
+
+    // RFC 6979 appendix A.2.5 signature of "test", checked against "sample"
+    let public_key = Issuer::Secp256r1(BytesN::from_array(
+        &env,
+        &hex_array(
+            "0460fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6\
+             7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299",
+        ),
+    ));
+    let signature = hex_bytes(
+        &env,
+        "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367\
+         019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083",
+    );
+
+    verify_signature(
+        &env,
+        &public_key,
+        &signature,
+        &Bytes::from_slice(&env, b"sample"),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_mint_certificate_ed25519_invalid_signature() {
+    let ctx = TestContext::new();
+    let (_, issuer) = generate_ed25519_issuer(&ctx.env);
+    let (other_sk, _) = generate_ed25519_issuer(&ctx.env);
+
+    ctx.mint_signed(&issuer, |message| {
+        sign_ed25519(&ctx.env, message, &other_sk)
+    });
+}
+
+#[test]
+#[should_panic(expected = "#105")]
+fn test_mint_certificate_ed25519_wrong_signature_length() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_ed25519_issuer(&ctx.env);
+
+    ctx.mint_signed(&issuer, |message| {
+        let mut signature = sign_ed25519(&ctx.env, message, &sk);
+        signature.push_back(0);
+        signature
+    });
+}
+
+#[test]
+fn test_mint_certificate_secp256r1_issuer() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_secp256r1_issuer(&ctx.env);
+
+    let (certificate_id, metadata) =
+        ctx.mint_signed(&issuer, |message| sign_secp256r1(&ctx.env, message, &sk));
+
+    assert_eq!(ctx.client.owner_of(&certificate_id), metadata.recipient);
+    assert_eq!(
+        ctx.client
+            .certificate_metadata(&certificate_id)
+            .metadata
+            .issuer,
+        issuer
+    );
+
+    assert!(ctx.client.verify_certificate(&certificate_id).valid);
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_mint_certificate_secp256r1_invalid_signature() {
+    let ctx = TestContext::new();
+    let (_, issuer) = generate_secp256r1_issuer(&ctx.env);
+    let (other_sk, _) = generate_secp256r1_issuer(&ctx.env);
+
+    ctx.mint_signed(&issuer, |message| {
+        sign_secp256r1(&ctx.env, message, &other_sk)
+    });
+}
+
+#[test]
+fn test_revoke_certificate_by_secp256r1_issuer() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_secp256r1_issuer(&ctx.env);
+
+    let (certificate_id, _) =
+        ctx.mint_signed(&issuer, |message| sign_secp256r1(&ctx.env, message, &sk));
+
+    let reason = String::from_str(&ctx.env, "academic fraud");
+    let message = build_revocation_message(&ctx.env, &ctx.nft_address, &certificate_id, &reason);
+    let signature = sign_secp256r1(&ctx.env, &message, &sk);
+
+    ctx.client
+        .issuer_revoke_certificate(&certificate_id, &reason, &signature);
+
+    let record = ctx.client.revocation(&certificate_id).unwrap();
+    assert_eq!(record.revoked_by, Revoker::Issuer(issuer));
+}
+
+#[test]
+fn test_issuer_profile_and_list_issuers() {
+    let ctx = TestContext::new();
+    let (_, first) = generate_issuer(&ctx.env);
+    let (_, second) = generate_ed25519_issuer(&ctx.env);
+    let (_, third) = generate_issuer(&ctx.env);
+
+    let mut profile = issuer_profile(&ctx.env);
+    ctx.client.add_issuer(&ctx.admin, &first, &profile);
+    profile.name = String::from_str(&ctx.env, "Stellar University");
+    ctx.client.add_issuer(&ctx.admin, &second, &profile);
+    ctx.add_issuer(&third);
+
+    assert_eq!(ctx.client.issuer_profile(&second), Some(profile.clone()));
+
+    let page = ctx.client.list_issuers(&1, &5);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get_unchecked(0), (1, profile));
+    assert_eq!(page.get_unchecked(1).0, 2);
+    assert_eq!(ctx.client.issuer_id(&third), Some(2));
+
+    ctx.client.remove_issuer(&ctx.admin, &first);
+    assert_eq!(ctx.client.issuer_profile(&first), None);
+    assert_eq!(ctx.client.list_issuers(&0, &5).len(), 2);
+}
+
+#[test]
+fn test_update_issuer() {
+    let ctx = TestContext::new();
+    let (_, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let mut profile = issuer_profile(&ctx.env);
+    profile.website = String::from_str(&ctx.env, "https://academy.tradoxus.com");
+    ctx.client.update_issuer(&ctx.admin, &issuer, &profile);
+
+    assert_eq!(ctx.client.issuer_profile(&issuer), Some(profile));
+}
+
+#[test]
+#[should_panic(expected = "#107")]
+fn test_update_issuer_fails_if_not_found() {
+    let ctx = TestContext::new();
+    let (_, issuer) = generate_issuer(&ctx.env);
+
+    ctx.client
+        .update_issuer(&ctx.admin, &issuer, &issuer_profile(&ctx.env));
+}
+
+#[test]
+#[should_panic(expected = "#120")]
+fn test_mint_certificate_fails_issuer_inactive() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let mut profile = issuer_profile(&ctx.env);
+    profile.active = false;
+    ctx.client.update_issuer(&ctx.admin, &issuer, &profile);
+
+    ctx.mint_from_request(&ctx.mint_request(&sk, &issuer, 0));
+}
+
+#[test]
+fn test_mint_certificate_course_scope() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+
+    let mut profile = issuer_profile(&ctx.env);
+    profile.course_scopes = vec![
+        &ctx.env,
+        String::from_str(&ctx.env, "course:rust-"),
+        String::from_str(&ctx.env, "course:solidity-"),
+    ];
+    ctx.client.add_issuer(&ctx.admin, &issuer, &profile);
+
+    let certificate_id = ctx.mint_from_request(&ctx.mint_request(&sk, &issuer, 0));
+    assert_eq!(ctx.client.issued_certificates(), certificate_id + 1);
+}
+
+#[test]
+#[should_panic(expected = "#121")]
+fn test_mint_certificate_fails_course_out_of_scope() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+
+    let mut profile = issuer_profile(&ctx.env);
+    profile.course_scopes = vec![&ctx.env, String::from_str(&ctx.env, "course:rust-")];
+    ctx.client.add_issuer(&ctx.admin, &issuer, &profile);
+
+    ctx.mint_from_request(&ctx.mint_request(&sk, &issuer, 0));
+}
+
+#[test]
+fn test_mint_certificate_quota_resets_each_period() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+
+    let mut profile = issuer_profile(&ctx.env);
+    profile.max_mints = Some(2);
+    profile.mint_period = 86400;
+    ctx.client.add_issuer(&ctx.admin, &issuer, &profile);
+
+    ctx.mint_from_request(&ctx.mint_request(&sk, &issuer, 0));
+    ctx.mint_from_request(&ctx.mint_request(&sk, &issuer, 1));
+
+    let request = ctx.mint_request(&sk, &issuer, 2);
+    let result = ctx.client.try_mint_certificate(&request);
+    assert_eq!(result, Err(Ok(Error::MintQuotaExceeded)));
+
+    ctx.env.ledger().with_mut(|li| li.timestamp += 86400);
+
+    ctx.mint_from_request(&ctx.mint_request(&sk, &issuer, 2));
+    assert_eq!(ctx.client.issued_certificates(), 3);
+}
+
+#[test]
+#[should_panic(expected = "#122")]
+fn test_mint_certificate_lifetime_quota() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+
+    let mut profile = issuer_profile(&ctx.env);
+    profile.max_mints = Some(1);
+    ctx.client.add_issuer(&ctx.admin, &issuer, &profile);
+
+    ctx.mint_from_request(&ctx.mint_request(&sk, &issuer, 0));
+
+    ctx.env.ledger().with_mut(|li| li.timestamp += 365 * 86400);
+
+    ctx.mint_from_request(&ctx.mint_request(&sk, &issuer, 1));
+}
+
+#[test]
+fn test_rotate_issuer_key() {
+    let ctx = TestContext::new();
+    let (old_sk, old_key) = generate_issuer(&ctx.env);
+    let (new_sk, new_key) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&old_key);
+    let issuer_id = ctx.client.issuer_id(&old_key).unwrap();
+
+    let request = ctx.mint_request(&old_sk, &old_key, 0);
+    let old_certificate = ctx.mint_from_request(&request);
+
+    ctx.env.ledger().with_mut(|li| li.timestamp += 1000);
+
+    let message = build_rotation_message(&ctx.env, &ctx.nft_address, &issuer_id, &new_key);
+    let signature = sign_message(&ctx.env, &message, old_sk);
+    ctx.client
+        .rotate_issuer_key(&issuer_id, &new_key, &signature.into());
+
+    let keys = ctx.client.issuer_keys(&issuer_id);
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys.get_unchecked(0).valid_until, Some(1000));
+    assert_eq!(
+        keys.get_unchecked(1),
+        IssuerKey {
+            key: new_key.clone(),
+            valid_from: 1000,
+            valid_until: None,
+        }
+    );
+    assert_eq!(ctx.client.issuer_id(&new_key), Some(issuer_id));
+    assert_eq!(
+        ctx.client.issuer_profile(&new_key),
+        ctx.client.issuer_profile(&old_key)
+    );
+
+    // certificates signed before the rotation stay verifiable
+    assert!(ctx.client.verify_certificate(&old_certificate).valid);
+
+    let new_certificate = ctx.mint_from_request(&ctx.mint_request(&new_sk, &new_key, 0));
+    assert_eq!(new_certificate, old_certificate + 1);
+}
+
+#[test]
+#[should_panic(expected = "#123")]
+fn test_mint_certificate_fails_with_rotated_key() {
+    let ctx = TestContext::new();
+    let (old_sk, old_key) = generate_issuer(&ctx.env);
+    let (_, new_key) = generate_issuer(&ctx.env);
+    let issuer_id = ctx
+        .client
+        .add_issuer(&ctx.admin, &old_key, &issuer_profile(&ctx.env));
+
+    let message = build_rotation_message(&ctx.env, &ctx.nft_address, &issuer_id, &new_key);
+    let signature = sign_message(&ctx.env, &message, old_sk.clone());
+    ctx.client
+        .rotate_issuer_key(&issuer_id, &new_key, &signature.into());
+
+    ctx.mint_from_request(&ctx.mint_request(&old_sk, &old_key, 0));
+}
+
+#[test]
+#[should_panic(expected = "#123")]
+fn test_mint_certificate_fails_backdated_before_rotation() {
+    let ctx = TestContext::new();
+    let (old_sk, old_key) = generate_issuer(&ctx.env);
+    let (new_sk, new_key) = generate_issuer(&ctx.env);
+    let issuer_id = ctx
+        .client
+        .add_issuer(&ctx.admin, &old_key, &issuer_profile(&ctx.env));
+
+    ctx.env.ledger().with_mut(|li| li.timestamp += 1000);
+
+    let message = build_rotation_message(&ctx.env, &ctx.nft_address, &issuer_id, &new_key);
+    let signature = sign_message(&ctx.env, &message, old_sk);
+    ctx.client
+        .rotate_issuer_key(&issuer_id, &new_key, &signature.into());
+
+    ctx.env.ledger().with_mut(|li| li.timestamp -= 1);
+    let request = ctx.mint_request(&new_sk, &new_key, 0);
+    ctx.env.ledger().with_mut(|li| li.timestamp += 1);
+
+    ctx.mint_from_request(&request);
+}
+
+#[test]
+fn test_verify_certificate_fails_if_key_rotated_before_issued_date() {
+    let ctx = TestContext::new();
+    let (old_sk, old_key) = generate_issuer(&ctx.env);
+    let (_, new_key) = generate_issuer(&ctx.env);
+    let issuer_id = ctx
+        .client
+        .add_issuer(&ctx.admin, &old_key, &issuer_profile(&ctx.env));
+
+    // a certificate dated after the key is retired
+    ctx.env.ledger().with_mut(|li| li.timestamp += 1000);
+    let request = ctx.mint_request(&old_sk, &old_key, 0);
+    ctx.env.ledger().with_mut(|li| li.timestamp -= 1000);
+    let certificate_id = ctx.mint_from_request(&request);
+
+    let message = build_rotation_message(&ctx.env, &ctx.nft_address, &issuer_id, &new_key);
+    let signature = sign_message(&ctx.env, &message, old_sk);
+    ctx.client
+        .rotate_issuer_key(&issuer_id, &new_key, &signature.into());
+
+    assert_eq!(
+        ctx.client.verify_certificate(&certificate_id).reasons,
+        vec![&ctx.env, VerificationFailure::IssuerKeyInactive]
+    );
+}
+
+#[test]
+#[should_panic(expected = "#105")]
+fn test_rotate_issuer_key_fails_invalid_signature() {
+    let ctx = TestContext::new();
+    let (_, old_key) = generate_issuer(&ctx.env);
+    let (new_sk, new_key) = generate_issuer(&ctx.env);
+    let issuer_id = ctx
+        .client
+        .add_issuer(&ctx.admin, &old_key, &issuer_profile(&ctx.env));
+
+    let message = build_rotation_message(&ctx.env, &ctx.nft_address, &issuer_id, &new_key);
+    let signature = sign_message(&ctx.env, &message, new_sk);
+    ctx.client
+        .rotate_issuer_key(&issuer_id, &new_key, &signature.into());
+}
+
+#[test]
+#[should_panic(expected = "#105")]
+fn test_rotate_issuer_key_signed_for_other_deployment() {
+    let ctx = TestContext::new();
+    let (old_sk, old_key) = generate_issuer(&ctx.env);
+    let (_, new_key) = generate_issuer(&ctx.env);
+    let issuer_id = ctx
+        .client
+        .add_issuer(&ctx.admin, &old_key, &issuer_profile(&ctx.env));
+
+    // the same identity ID and key on another contract
+    let other_contract = Address::generate(&ctx.env);
+    let message = build_rotation_message(&ctx.env, &other_contract, &issuer_id, &new_key);
+    let signature = sign_message(&ctx.env, &message, old_sk);
+    ctx.client
+        .rotate_issuer_key(&issuer_id, &new_key, &signature.into());
+}
+
+#[test]
+#[should_panic(expected = "#106")]
+fn test_rotate_issuer_key_fails_if_key_registered() {
+    let ctx = TestContext::new();
+    let (old_sk, old_key) = generate_issuer(&ctx.env);
+    let (_, other_key) = generate_issuer(&ctx.env);
+    let issuer_id = ctx
+        .client
+        .add_issuer(&ctx.admin, &old_key, &issuer_profile(&ctx.env));
+    ctx.add_issuer(&other_key);
+
+    let message = build_rotation_message(&ctx.env, &ctx.nft_address, &issuer_id, &other_key);
+    let signature = sign_message(&ctx.env, &message, old_sk);
+    ctx.client
+        .rotate_issuer_key(&issuer_id, &other_key, &signature.into());
+}
+
+#[test]
+fn test_rotate_account_issuer_to_ed25519_key() {
+    let ctx = TestContext::new();
+    let institution = Address::generate(&ctx.env);
+    let old_key = Issuer::Account(institution.clone());
+    let (_, new_key) = generate_ed25519_issuer(&ctx.env);
+    let issuer_id = ctx
+        .client
+        .add_issuer(&ctx.admin, &old_key, &issuer_profile(&ctx.env));
+
+    ctx.client
+        .rotate_issuer_key(&issuer_id, &new_key, &Bytes::new(&ctx.env));
+
+    assert_eq!(ctx.env.auths()[0].0, institution);
+    assert_eq!(ctx.client.issuer_id(&new_key), Some(issuer_id));
+}
+
+#[test]
+fn test_remove_issuer_distrusts_certificates() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, _) = ctx.mint_to(&Address::generate(&ctx.env), None, None);
+
+    ctx.env.ledger().with_mut(|li| li.timestamp += 1000);
+    ctx.client.remove_issuer(&ctx.admin, &metadata.issuer);
+
+    // the key was active on the issue date, but the issuer is no longer trusted
+    assert_eq!(
+        ctx.client.verify_certificate(&certificate_id).reasons,
+        vec![&ctx.env, VerificationFailure::IssuerNotTrusted]
+    );
+    assert_eq!(ctx.client.issuer_profile(&metadata.issuer), None);
+}
+
+#[test]
+fn test_add_issuer_restores_removed_issuer() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, sk) = ctx.mint_to(&Address::generate(&ctx.env), None, None);
+    let issuer_id = ctx.client.issuer_id(&metadata.issuer).unwrap();
+
+    ctx.env.ledger().with_mut(|li| li.timestamp += 1000);
+    ctx.client.remove_issuer(&ctx.admin, &metadata.issuer);
+
+    // issued between the removal and the restoration
+    let request = ctx.mint_request(&sk, &metadata.issuer, 1);
+    assert_eq!(
+        ctx.client.try_mint_certificate(&request),
+        Err(Ok(Error::NotIssuer))
+    );
+
+    ctx.env.ledger().with_mut(|li| li.timestamp += 1000);
+    let profile = issuer_profile(&ctx.env);
+    let restored_id = ctx
+        .client
+        .add_issuer(&ctx.admin, &metadata.issuer, &profile);
+
+    assert_eq!(restored_id, issuer_id);
+    assert_eq!(ctx.client.issuer_profile(&metadata.issuer), Some(profile));
+    assert_eq!(
+        ctx.client.issuer_keys(&issuer_id),
+        vec![
+            &ctx.env,
+            IssuerKey {
+                key: metadata.issuer.clone(),
+                valid_from: 0,
+                valid_until: Some(1000),
+            },
+            IssuerKey {
+                key: metadata.issuer.clone(),
+                valid_from: 2000,
+                valid_until: None,
+            },
+        ]
+    );
+    assert_eq!(ctx.client.list_issuers(&0, &5).len(), 1);
+
+    assert!(ctx.client.verify_certificate(&certificate_id).valid);
+    assert_eq!(
+        ctx.client.try_mint_certificate(&request),
+        Err(Ok(Error::IssuerKeyInactive))
+    );
+    let reissued = ctx.mint_from_request(&ctx.mint_request(&sk, &metadata.issuer, 2));
+    assert!(ctx.client.verify_certificate(&reissued).valid);
+}
+
+#[test]
+#[should_panic(expected = "#106")]
+fn test_add_issuer_fails_for_retired_key_of_removed_issuer() {
+    let ctx = TestContext::new();
+    let (old_sk, old_key) = generate_issuer(&ctx.env);
+    let (_, new_key) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&old_key);
+    let issuer_id = ctx.client.issuer_id(&old_key).unwrap();
+
+    let message = build_rotation_message(&ctx.env, &ctx.nft_address, &issuer_id, &new_key);
+    let signature = sign_message(&ctx.env, &message, old_sk);
+    ctx.client
+        .rotate_issuer_key(&issuer_id, &new_key, &signature.into());
+    ctx.client.remove_issuer(&ctx.admin, &new_key);
+
+    // only the key the identity was removed with restores it
+    ctx.add_issuer(&old_key);
+}
+
+#[test]
+fn test_revoke_certificate_by_rotated_issuer() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, old_sk) = ctx.mint_to(&Address::generate(&ctx.env), None, None);
+    let issuer_id = ctx.client.issuer_id(&metadata.issuer).unwrap();
+    let (new_sk, new_key) = generate_issuer(&ctx.env);
+
+    let message = build_rotation_message(&ctx.env, &ctx.nft_address, &issuer_id, &new_key);
+    let signature = sign_message(&ctx.env, &message, old_sk);
+    ctx.client
+        .rotate_issuer_key(&issuer_id, &new_key, &signature.into());
+
+    let reason = String::from_str(&ctx.env, "academic fraud");
+    let message = build_revocation_message(&ctx.env, &ctx.nft_address, &certificate_id, &reason);
+    let signature = sign_message(&ctx.env, &message, new_sk);
+    ctx.client
+        .issuer_revoke_certificate(&certificate_id, &reason, &signature.into());
+
+    let record = ctx.client.revocation(&certificate_id).unwrap();
+    assert_eq!(record.revoked_by, Revoker::Issuer(new_key));
+}
+
+#[test]
+fn test_grant_and_revoke_role() {
+    let ctx = TestContext::new();
+    let operator = Address::generate(&ctx.env);
+
+    assert!(!ctx.client.has_role(&Role::IssuerManager, &operator));
+
+    ctx.client.grant_role(&Role::IssuerManager, &operator);
+    assert_eq!(ctx.env.auths()[0].0, ctx.admin);
+    assert!(ctx.client.has_role(&Role::IssuerManager, &operator));
+    assert!(!ctx.client.has_role(&Role::Revoker, &operator));
+
+    ctx.client.revoke_role(&Role::IssuerManager, &operator);
+    assert!(!ctx.client.has_role(&Role::IssuerManager, &operator));
+}
+
+#[test]
+fn test_issuer_manager_adds_issuer() {
+    let ctx = TestContext::new();
+    let operator = Address::generate(&ctx.env);
+    ctx.client.grant_role(&Role::IssuerManager, &operator);
+
+    let (_, issuer) = generate_issuer(&ctx.env);
+    ctx.client
+        .add_issuer(&operator, &issuer, &issuer_profile(&ctx.env));
+
+    assert_eq!(ctx.env.auths()[0].0, operator);
+    assert_eq!(ctx.client.issuer_id(&issuer), Some(0));
+
+    ctx.client.remove_issuer(&operator, &issuer);
+    assert_eq!(ctx.client.issuer_profile(&issuer), None);
+}
+
+#[test]
+#[should_panic(expected = "#104")]
+fn test_add_issuer_fails_without_role() {
+    let ctx = TestContext::new();
+    let operator = Address::generate(&ctx.env);
+    ctx.client.grant_role(&Role::Revoker, &operator);
+
+    let (_, issuer) = generate_issuer(&ctx.env);
+    ctx.client
+        .add_issuer(&operator, &issuer, &issuer_profile(&ctx.env));
+}
+
+#[test]
+fn test_revoke_certificate_by_revoker() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint_to(&Address::generate(&ctx.env), None, None);
+    let operator = Address::generate(&ctx.env);
+    ctx.client.grant_role(&Role::Revoker, &operator);
+
+    let reason = String::from_str(&ctx.env, "academic fraud");
+    ctx.client
+        .revoke_certificate(&operator, &certificate_id, &reason);
+
+    let record = ctx.client.revocation(&certificate_id).unwrap();
+    assert_eq!(record.revoked_by, Revoker::Operator(operator));
+}
+
+#[test]
+#[should_panic(expected = "#104")]
+fn test_revoke_certificate_fails_after_role_revoked() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint_to(&Address::generate(&ctx.env), None, None);
+    let operator = Address::generate(&ctx.env);
+    ctx.client.grant_role(&Role::Revoker, &operator);
+    ctx.client.revoke_role(&Role::Revoker, &operator);
+
+    let reason = String::from_str(&ctx.env, "academic fraud");
+    ctx.client
+        .revoke_certificate(&operator, &certificate_id, &reason);
+}
+
+#[test]
+fn test_new_admin_keeps_privileges_after_accept() {
+    let ctx = TestContext::new();
+    let new_admin = Address::generate(&ctx.env);
+    ctx.client.transfer_admin(&new_admin);
+    ctx.client.accept_admin();
+
+    let operator = Address::generate(&ctx.env);
+    ctx.client.grant_role(&Role::Pauser, &operator);
+
+    assert_eq!(ctx.env.auths()[0].0, new_admin);
+    assert!(ctx.client.has_role(&Role::Pauser, &operator));
+}
+
+#[test]
+fn test_pause_and_unpause() {
+    let ctx = TestContext::new();
+    let pauser = Address::generate(&ctx.env);
+    ctx.client.grant_role(&Role::Pauser, &pauser);
+
+    ctx.client.pause(&pauser, &Some(Operation::Mint));
+    assert_eq!(ctx.env.auths()[0].0, pauser);
+    ctx.client.pause(&pauser, &None);
+
+    assert_eq!(
+        ctx.client.paused(),
+        PauseState {
+            global: true,
+            operations: vec![&ctx.env, Operation::Mint],
+        }
+    );
+
+    // operations paused individually stay paused after a global unpause
+    ctx.client.unpause(&pauser, &None);
+    let result = ctx.client.try_mint_certificate(&account_request(
+        &ctx.env,
+        &Issuer::Account(Address::generate(&ctx.env)),
+    ));
+    assert_eq!(result, Err(Ok(Error::Paused)));
+
+    ctx.client.unpause(&pauser, &Some(Operation::Mint));
+    assert_eq!(
+        ctx.client.paused(),
+        PauseState {
+            global: false,
+            operations: Vec::new(&ctx.env),
+        }
+    );
+    ctx.mint();
+}
+
+#[test]
+#[should_panic(expected = "#104")]
+fn test_pause_fails_without_role() {
+    let ctx = TestContext::new();
+    let operator = Address::generate(&ctx.env);
+    ctx.client.grant_role(&Role::Revoker, &operator);
+
+    ctx.client.pause(&operator, &None);
+}
+
+#[test]
+#[should_panic(expected = "#124")]
+fn test_batch_mint_fails_when_paused() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+    ctx.client.pause(&ctx.admin, &None);
+
+    ctx.client
+        .batch_mint_certificates(&vec![&ctx.env, ctx.mint_request(&sk, &issuer, 0)], &false);
+}
+
+#[test]
+#[should_panic(expected = "#124")]
+fn test_transfer_fails_when_paused() {
+    let ctx = TestContext::new();
+    let owner = Address::generate(&ctx.env);
+    let (certificate_id, _, _) = ctx.mint_to(&owner, None, None);
+    ctx.client.pause(&ctx.admin, &Some(Operation::Transfer));
+
+    ctx.client
+        .transfer(&owner, &Address::generate(&ctx.env), &certificate_id);
+}
+
+#[test]
+#[should_panic(expected = "#124")]
+fn test_revoke_fails_when_paused() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint_to(&Address::generate(&ctx.env), None, None);
+    ctx.client.pause(&ctx.admin, &Some(Operation::Revoke));
+
+    let reason = String::from_str(&ctx.env, "academic fraud");
+    ctx.client
+        .revoke_certificate(&ctx.admin, &certificate_id, &reason);
+}
+
+#[test]
+fn test_pause_mint_allows_transfers() {
+    let ctx = TestContext::new();
+    let owner = Address::generate(&ctx.env);
+    let recipient = Address::generate(&ctx.env);
+    let (certificate_id, _, _) = ctx.mint_to(&owner, None, None);
+    ctx.client.pause(&ctx.admin, &Some(Operation::Mint));
+
+    ctx.client.transfer(&owner, &recipient, &certificate_id);
+
+    assert_eq!(ctx.client.owner_of(&certificate_id), recipient);
+}
+
+/// Smallest wasm module the host accepts as contract code. It exports no
+/// functions, so calls into a contract upgraded to it fail.
+fn stub_wasm(env: &Env) -> Bytes {
+    let mut wasm = Bytes::from_slice(env, b"\0asm\x01\0\0\0");
+    // custom section with the protocol 22 interface version
+    wasm.extend_from_slice(&[0, 30, 17]);
+    wasm.extend_from_slice(b"contractenvmetav0");
+    wasm.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 22, 0, 0, 0, 0]);
+    wasm
+}
+
+/// Writes `count` certificates signed by a single issuer in the schema version 1
+/// layout and returns the issuer's key with the signed messages
+fn populate_legacy_contract(
+    ctx: &TestContext,
+    count: u32,
+    listed: bool,
+) -> (BytesN<65>, std::vec::Vec<Bytes>) {
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    let Issuer::Secp256k1(public_key) = issuer else {
+        unreachable!()
+    };
+    let owner = Address::generate(&ctx.env);
+    let mut messages = std::vec::Vec::new();
+
+    ctx.env.as_contract(&ctx.nft_address, || {
+        let instance = ctx.env.storage().instance();
+        instance.remove(&VERSION_KEY);
+        instance.remove(&POLICY_KEY);
+
+        let issuers = match listed {
+            true => vec![&ctx.env, public_key.clone()],
+            false => Vec::new(&ctx.env),
+        };
+        instance.set(&ISSUERS_KEY, &issuers);
+        instance.set(&COUNTER_KEY, &count);
+        instance.set(&DataKey::CerticateRegister(owner.clone()), &count);
+
+        for certificate_id in 0..count {
+            let course_id = String::from_str(&ctx.env, "course:solidity-bootcamp-2025");
+            let metadata_uri = String::from_str(
+                &ctx.env,
+                "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
+            );
+            let issued_date = 1_700_000_000 + certificate_id as u64;
+
+            // version 1 messages only covered these fields
+            let fields = vec![
+                &ctx.env,
+                string_to_bytes(&ctx.env, course_id.clone()),
+                string_to_bytes(&ctx.env, owner.to_string()),
+                string_to_bytes(&ctx.env, metadata_uri.clone()),
+                number_to_string_bytes(&ctx.env, issued_date),
+            ];
+            let len = fields.iter().map(|field| field.len()).sum::<u32>();
+            let message = concatenate_bytes(
+                &ctx.env,
+                vec![
+                    &ctx.env,
+                    string_to_bytes(
+                        &ctx.env,
+                        String::from_str(&ctx.env, "\x19Tradoxus Signed Message:\n"),
+                    ),
+                    number_to_string_bytes(&ctx.env, len.into()),
+                    concatenate_bytes(&ctx.env, fields),
+                ],
+            );
+
+            let metadata = LegacyCertificateMetadata {
+                course_id,
+                issued_date,
+                issuer: public_key.clone(),
+                metadata_uri,
+                signature: sign_message(&ctx.env, &message, sk.clone()),
+                recipient: owner.clone(),
+            };
+
+            instance.set(&DataKey::CertificateOwner(certificate_id), &owner);
+            instance.set(&DataKey::CertificateMetadata(certificate_id), &metadata);
+            messages.push(message);
+        }
+    });
+
+    (public_key, messages)
+}
+
+#[test]
+fn test_upgrade() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint_to(&Address::generate(&ctx.env), None, None);
+    let upgrader = Address::generate(&ctx.env);
+    ctx.client.grant_role(&Role::Upgrader, &upgrader);
+
+    let wasm_hash = ctx.env.deployer().upload_contract_wasm(stub_wasm(&ctx.env));
+    ctx.client.upgrade(&upgrader, &wasm_hash);
+
+    assert_eq!(ctx.env.auths()[0].0, upgrader);
+    let events = ctx.env.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &ctx.env,
+            (
+                ctx.nft_address.clone(),
+                (Symbol::new(&ctx.env, "upgraded"),).into_val(&ctx.env),
+                wasm_hash.into_val(&ctx.env),
+            )
+        ]
+    );
+
+    // the contract now runs the new code on top of the existing storage
+    assert!(ctx.client.try_owner_of(&certificate_id).is_err());
+    ctx.env.as_contract(&ctx.nft_address, || {
+        assert!(ctx
+            .env
+            .storage()
+            .persistent()
+            .has(&DataKey::CertificateOwner(certificate_id)));
+    });
+}
+
+#[test]
+#[ignore = "needs the compiled contract, run by `make test`"]
+fn test_upgrade_populated_v1_contract() {
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let name = String::from_str(&env, "Traxodus Cerficates");
+    let symbol = String::from_str(&env, "TxCerts");
+    let address = env.register(v1::WASM, (name.clone(), symbol));
+
+    // populate the released code through its own API
+    let old = v1::Client::new(&env, &address);
+    old.initialize(&admin);
+    let (sk, _, public_key) = generate_keypair(&env);
+    old.add_issuer(&public_key);
+
+    let owner = Address::generate(&env);
+    for course_id in ["course:solidity-bootcamp-2025", "course:rust-2025"] {
+        let metadata = CertificateMetadata {
+            course_id: String::from_str(&env, course_id),
+            issued_date: env.ledger().timestamp(),
+            valid_from: None,
+            expires_at: None,
+            nonce: 0,
+            issuer: Issuer::Secp256k1(public_key.clone()),
+            metadata_uri: String::from_str(
+                &env,
+                "ipfs://QmZkN8nsyK5cWmKL9BfEzDvDU1DqYKdEgqPtAGh8qMhjBd",
+            ),
+            metadata_hash: BytesN::from_array(&env, &[0; 32]),
+            signature: Bytes::new(&env),
+            recipient: owner.clone(),
+        };
+        let message = build_legacy_certificate_message(&env, &metadata);
+
+        old.mint_certificate(
+            &metadata.recipient,
+            &metadata.course_id,
+            &metadata.metadata_uri,
+            &public_key,
+            &metadata.issued_date,
+            &sign_message(&env, &message, sk.clone()),
+        );
+    }
+
+    // version 1 code has no `upgrade` entry point, so swap in the current code directly
+    let wasm_hash = env
+        .deployer()
+        .upload_contract_wasm(contract_wasm().as_slice());
+    env.as_contract(&address, || {
+        env.deployer()
+            .update_current_contract_wasm(wasm_hash.clone())
+    });
+
+    let client = CertificateNFTClient::new(&env, &address);
+    assert_eq!(client.version(), 1);
+    assert!(client.migrate(&admin));
+    assert_eq!(client.version(), SCHEMA_VERSION);
+
+    assert_eq!(client.name(), name);
+    assert_eq!(client.issued_certificates(), 2);
+    assert_eq!(client.balance(&owner), 2);
+    assert_eq!(client.tokens_of_owner(&owner, &0, &10), vec![&env, 0, 1]);
+    for certificate_id in 0..2 {
+        assert_eq!(client.owner_of(&certificate_id), owner);
+        assert!(client.verify_certificate(&certificate_id).valid);
+    }
+    assert!(client
+        .issuer_id(&Issuer::Secp256k1(public_key.clone()))
+        .is_some());
+
+    // the migrated contract can be upgraded through its own entry point
+    client.upgrade(&admin, &wasm_hash);
+    assert_eq!(client.version(), SCHEMA_VERSION);
+}
+
+#[test]
+#[should_panic(expected = "#104")]
+fn test_upgrade_fails_without_role() {
+    let ctx = TestContext::new();
+    let operator = Address::generate(&ctx.env);
+    ctx.client.grant_role(&Role::Pauser, &operator);
+
+    let wasm_hash = ctx.env.deployer().upload_contract_wasm(stub_wasm(&ctx.env));
+    ctx.client.upgrade(&operator, &wasm_hash);
+}
+
+#[test]
+fn test_migrate_is_noop_on_current_schema() {
+    let ctx = TestContext::new();
+    ctx.mint();
+
+    assert_eq!(ctx.client.version(), SCHEMA_VERSION);
+    assert!(ctx.client.migrate(&ctx.admin));
+    assert_eq!(ctx.client.issued_certificates(), 1);
+}
+
+#[test]
+fn test_migrate_from_v1() {
+    let ctx = TestContext::new();
+    let (public_key, messages) = populate_legacy_contract(&ctx, 3, true);
+    let issuer = Issuer::Secp256k1(public_key);
+    assert_eq!(ctx.client.version(), 1);
+
+    assert!(ctx.client.migrate(&ctx.admin));
+    let events = ctx.env.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &ctx.env,
+            (
+                ctx.nft_address.clone(),
+                (Symbol::new(&ctx.env, "migrated"),).into_val(&ctx.env),
+                SCHEMA_VERSION.into_val(&ctx.env),
+            )
+        ]
+    );
+    assert_eq!(ctx.client.version(), SCHEMA_VERSION);
+
+    let issuer_id = ctx.client.issuer_id(&issuer).unwrap();
+    assert_eq!(
+        ctx.client.list_issuers(&0, &10).get_unchecked(0).0,
+        issuer_id
+    );
+    assert_eq!(ctx.client.transfer_policy(), TransferPolicy::Soulbound);
+
+    let detail = ctx.client.certificate_metadata(&2);
+    let owner = detail.owner.clone();
+    assert_eq!(detail.metadata.issuer, issuer);
+    assert_eq!(detail.status, CertificateStatus::Active);
+    assert_eq!(
+        ctx.client.tokens_of_owner(&owner, &0, &10),
+        vec![&ctx.env, 0, 1, 2]
+    );
+    assert_eq!(ctx.client.balance(&owner), 3);
+    assert_eq!(
+        ctx.client
+            .certificates_for_course(&detail.metadata.course_id, &0, &10)
+            .len(),
+        3
+    );
+
+    for (certificate_id, message) in messages.iter().enumerate() {
+        let certificate_id = certificate_id as u32;
+        let metadata = ctx.client.certificate_metadata(&certificate_id).metadata;
+        assert_eq!(
+            build_legacy_certificate_message(&ctx.env, &metadata),
+            *message
+        );
+        assert!(ctx.client.verify_certificate(&certificate_id).valid);
+    }
+
+    ctx.env.as_contract(&ctx.nft_address, || {
+        let instance = ctx.env.storage().instance();
+        assert!(!instance.has(&DataKey::CertificateMetadata(0)));
+        assert!(!instance.has(&DataKey::CerticateRegister(owner.clone())));
+    });
+
+    // the migrated contract keeps working with the new layout
+    ctx.mint();
+    assert_eq!(ctx.client.issued_certificates(), 4);
+}
+
+#[test]
+fn test_migrate_from_v1_in_batches() {
+    let ctx = TestContext::new();
+    populate_legacy_contract(&ctx, MIGRATION_BATCH_SIZE + 5, true);
+
+    assert!(!ctx.client.migrate(&ctx.admin));
+    assert_eq!(ctx.client.version(), 1);
+    assert_eq!(
+        ctx.client.owner_of(&(MIGRATION_BATCH_SIZE - 1)),
+        ctx.client.owner_of(&0)
+    );
+    assert!(ctx.client.try_owner_of(&MIGRATION_BATCH_SIZE).is_err());
+
+    assert!(ctx.client.migrate(&ctx.admin));
+    assert_eq!(ctx.client.version(), SCHEMA_VERSION);
+    assert_eq!(
+        ctx.client.balance(&ctx.client.owner_of(&0)),
+        MIGRATION_BATCH_SIZE + 5
+    );
+}
+
+#[test]
+fn test_operations_fail_until_migrated() {
+    let ctx = TestContext::new();
+    let (public_key, _) = populate_legacy_contract(&ctx, MIGRATION_BATCH_SIZE + 5, true);
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    let profile = issuer_profile(&ctx.env);
+
+    assert!(!ctx.client.migrate(&ctx.admin));
+
+    assert_eq!(
+        ctx.client.try_add_issuer(&ctx.admin, &issuer, &profile),
+        Err(Ok(Error::MigrationPending))
+    );
+    assert_eq!(
+        ctx.client
+            .try_mint_certificate(&ctx.mint_request(&sk, &issuer, 0)),
+        Err(Ok(Error::MigrationPending))
+    );
+    let reason = String::from_str(&ctx.env, "issued by mistake");
+    assert_eq!(
+        ctx.client.try_revoke_certificate(&ctx.admin, &0, &reason),
+        Err(Ok(Error::MigrationPending))
+    );
+    assert_eq!(
+        ctx.client
+            .try_remove_issuer(&ctx.admin, &Issuer::Secp256k1(public_key)),
+        Err(Ok(Error::MigrationPending))
+    );
+
+    assert!(ctx.client.migrate(&ctx.admin));
+
+    ctx.client.add_issuer(&ctx.admin, &issuer, &profile);
+    let certificate_id = ctx.mint_from_request(&ctx.mint_request(&sk, &issuer, 0));
+    assert_eq!(certificate_id, MIGRATION_BATCH_SIZE + 5);
+}
+
+#[test]
+fn test_migrate_from_v1_distrusts_removed_issuer_certificates() {
+    let ctx = TestContext::new();
+    let (public_key, _) = populate_legacy_contract(&ctx, 1, false);
+    let issuer = Issuer::Secp256k1(public_key);
+
+    ctx.env.ledger().with_mut(|li| li.timestamp = 1_800_000_000);
+    assert!(ctx.client.migrate(&ctx.admin));
+
+    let issuer_id = ctx.client.issuer_id(&issuer).unwrap();
+    assert_eq!(ctx.client.issuer_profile(&issuer), None);
+    assert!(ctx
+        .client
+        .issuer_keys(&issuer_id)
+        .get_unchecked(0)
+        .valid_until
+        .is_some());
+    assert_eq!(
+        ctx.client.verify_certificate(&0).reasons,
+        vec![&ctx.env, VerificationFailure::IssuerNotTrusted]
+    );
+}
+
+#[test]
+fn test_minted_event() {
+    let ctx = TestContext::new();
+    ctx.mint();
+    let (certificate_id, metadata, _) = ctx.mint();
+    let events = ctx.env.events().all();
+
+    let issuer_id = ctx.client.issuer_id(&metadata.issuer).unwrap();
+    let uri_hash = ctx
+        .env
+        .crypto()
+        .sha256(&string_to_bytes(&ctx.env, metadata.metadata_uri.clone()));
+    let payload = MintedEvent {
+        issuer_id,
+        issuer: metadata.issuer.clone(),
+        course_id: metadata.course_id.clone(),
+        metadata_uri_hash: uri_hash.into(),
+    };
+
+    // the event carries the ID of the minted certificate, not the next one
+    assert_eq!(certificate_id, 1);
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &ctx.env,
+            (
+                ctx.nft_address.clone(),
+                (symbol_short!("minted"), metadata.recipient, certificate_id).into_val(&ctx.env),
+                payload.into_val(&ctx.env),
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_revoked_event() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint();
+
+    let reason = String::from_str(&ctx.env, "issued by mistake");
+    ctx.client
+        .revoke_certificate(&ctx.admin, &certificate_id, &reason);
+
+    let record = RevocationRecord {
+        reason,
+        revoked_at: ctx.env.ledger().timestamp(),
+        revoked_by: Revoker::Operator(ctx.admin.clone()),
+    };
+    ctx.assert_last_event(
+        (symbol_short!("revoked"), certificate_id).into_val(&ctx.env),
+        record.into_val(&ctx.env),
+    );
+}
+
+#[test]
+fn test_issuer_events() {
+    let ctx = TestContext::new();
+    let (old_sk, old_key) = generate_issuer(&ctx.env);
+    let (_, new_key) = generate_issuer(&ctx.env);
+    let mut profile = issuer_profile(&ctx.env);
+
+    let issuer_id = ctx.client.add_issuer(&ctx.admin, &old_key, &profile);
+    ctx.assert_last_event(
+        (Symbol::new(&ctx.env, "issuer_added"), issuer_id).into_val(&ctx.env),
+        (old_key.clone(), profile.clone()).into_val(&ctx.env),
+    );
+
+    profile.active = false;
+    ctx.client.update_issuer(&ctx.admin, &old_key, &profile);
+    ctx.assert_last_event(
+        (Symbol::new(&ctx.env, "issuer_updated"), issuer_id).into_val(&ctx.env),
+        profile.into_val(&ctx.env),
+    );
+
+    let message = build_rotation_message(&ctx.env, &ctx.nft_address, &issuer_id, &new_key);
+    let signature = sign_message(&ctx.env, &message, old_sk);
+    ctx.client
+        .rotate_issuer_key(&issuer_id, &new_key, &signature.into());
+    ctx.assert_last_event(
+        (Symbol::new(&ctx.env, "issuer_key_rotated"), issuer_id).into_val(&ctx.env),
+        KeyRotatedEvent {
+            old_key: old_key.clone(),
+            new_key: new_key.clone(),
+        }
+        .into_val(&ctx.env),
+    );
+
+    // removing by a retired key reports the key that was retired
+    ctx.client.remove_issuer(&ctx.admin, &old_key);
+    ctx.assert_last_event(
+        (Symbol::new(&ctx.env, "issuer_removed"), issuer_id).into_val(&ctx.env),
+        new_key.into_val(&ctx.env),
+    );
+}
+
+#[test]
+fn test_admin_transfer_events() {
+    let ctx = TestContext::new();
+    let new_admin = Address::generate(&ctx.env);
+
+    ctx.client.transfer_admin(&new_admin);
+    ctx.assert_last_event(
+        (Symbol::new(&ctx.env, "admin_proposed"), ctx.admin.clone()).into_val(&ctx.env),
+        new_admin.into_val(&ctx.env),
+    );
+
+    ctx.client.accept_admin();
+    ctx.assert_last_event(
+        (Symbol::new(&ctx.env, "admin_accepted"), ctx.admin.clone()).into_val(&ctx.env),
+        new_admin.into_val(&ctx.env),
+    );
+}
+
+#[test]
+fn test_config_events() {
+    let ctx = TestContext::new();
+
+    ctx.client.set_ttl_config(&1000, &5000);
+    ctx.assert_last_event(
+        (Symbol::new(&ctx.env, "ttl_config_set"),).into_val(&ctx.env),
+        (1000u32, 5000u32).into_val(&ctx.env),
+    );
+
+    ctx.client.set_unique_completion(&true);
+    ctx.assert_last_event(
+        (Symbol::new(&ctx.env, "unique_completion_set"),).into_val(&ctx.env),
+        true.into_val(&ctx.env),
+    );
+
+    ctx.client.set_recovery_delay(&86400);
+    ctx.assert_last_event(
+        (Symbol::new(&ctx.env, "recovery_delay_set"),).into_val(&ctx.env),
+        86400u64.into_val(&ctx.env),
+    );
+}
+
+#[test]
+fn test_verify_presentation_signed_by_account() {
+    let ctx = TestContext::new();
+    let (holder_sk, holder) = generate_account(&ctx.env);
+    let (certificate_id, _, _) = ctx.mint_to(&holder, None, None);
+
+    let challenge = Bytes::from_array(&ctx.env, b"employer-challenge-42");
+    let message =
+        build_presentation_message(&ctx.env, &ctx.nft_address, &certificate_id, &challenge);
+    let signature = sign_ed25519(&ctx.env, &message, &holder_sk);
+
+    let result = ctx
+        .client
+        .verify_presentation(&certificate_id, &challenge, &signature);
+
+    assert!(result.valid);
+    assert!(ctx.env.auths().is_empty());
+}
+
+#[test]
+fn test_verify_presentation_authorized_by_owner() {
+    let ctx = TestContext::new();
+    let holder = Address::generate(&ctx.env);
+    let (certificate_id, _, _) = ctx.mint_to(&holder, None, None);
+
+    let challenge = Bytes::from_array(&ctx.env, b"employer-challenge-42");
+    let result = ctx
+        .client
+        .verify_presentation(&certificate_id, &challenge, &Bytes::new(&ctx.env));
+
+    assert!(result.valid);
+    assert_eq!(ctx.env.auths()[0].0, holder);
+}
+
+#[test]
+fn test_verify_presentation_fails_for_contract_holder_signature() {
+    let ctx = TestContext::new();
+    let (holder_sk, _) = generate_account(&ctx.env);
+    let (certificate_id, _, _) = ctx.mint_to(&Address::generate(&ctx.env), None, None);
+
+    let challenge = Bytes::from_array(&ctx.env, b"employer-challenge-42");
+    let message =
+        build_presentation_message(&ctx.env, &ctx.nft_address, &certificate_id, &challenge);
+    let signature = sign_ed25519(&ctx.env, &message, &holder_sk);
+
+    let result = ctx
+        .client
+        .verify_presentation(&certificate_id, &challenge, &signature);
+
+    assert!(!result.valid);
+    assert_eq!(
+        result.reasons,
+        vec![&ctx.env, VerificationFailure::InvalidHolderProof]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_verify_presentation_fails_if_not_signed_by_owner() {
+    let ctx = TestContext::new();
+    let (_, holder) = generate_account(&ctx.env);
+    let (other_sk, _) = generate_account(&ctx.env);
+    let (certificate_id, _, _) = ctx.mint_to(&holder, None, None);
+
+    let challenge = Bytes::from_array(&ctx.env, b"employer-challenge-42");
+    let message =
+        build_presentation_message(&ctx.env, &ctx.nft_address, &certificate_id, &challenge);
+    let signature = sign_ed25519(&ctx.env, &message, &other_sk);
+
+    ctx.client
+        .verify_presentation(&certificate_id, &challenge, &signature);
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_verify_presentation_signed_for_other_deployment() {
+    let ctx = TestContext::new();
+    let (holder_sk, holder) = generate_account(&ctx.env);
+    let (certificate_id, _, _) = ctx.mint_to(&holder, None, None);
+
+    // the same holder, certificate ID and challenge on another contract
+    let challenge = Bytes::from_array(&ctx.env, b"employer-challenge-42");
+    let other_contract = Address::generate(&ctx.env);
+    let message =
+        build_presentation_message(&ctx.env, &other_contract, &certificate_id, &challenge);
+    let signature = sign_ed25519(&ctx.env, &message, &holder_sk);
+
+    ctx.client
+        .verify_presentation(&certificate_id, &challenge, &signature);
+}
+
+#[test]
+fn test_verify_presentation_reports_certificate_failures() {
+    let ctx = TestContext::new();
+    let (holder_sk, holder) = generate_account(&ctx.env);
+    let (certificate_id, _, _) = ctx.mint_to(&holder, None, None);
+
+    let reason = String::from_str(&ctx.env, "issued by mistake");
+    ctx.client
+        .revoke_certificate(&ctx.admin, &certificate_id, &reason);
+
+    let challenge = Bytes::from_array(&ctx.env, b"employer-challenge-42");
+    let message =
+        build_presentation_message(&ctx.env, &ctx.nft_address, &certificate_id, &challenge);
+    let signature = sign_ed25519(&ctx.env, &message, &holder_sk);
+
+    let result = ctx
+        .client
+        .verify_presentation(&certificate_id, &challenge, &signature);
+
+    assert_eq!(result.reasons, vec![&ctx.env, VerificationFailure::Revoked]);
+}
+
+#[test]
+fn test_burn() {
+    let ctx = TestContext::new();
+    let owner = Address::generate(&ctx.env);
+    let (first_id, _, _) = ctx.mint_to(&owner, None, None);
+    let (certificate_id, metadata, _) = ctx.mint_to(&owner, None, None);
+    ctx.env.ledger().with_mut(|li| li.sequence_number = 4242);
+
+    ctx.client.burn(&owner, &certificate_id);
+
+    let record = BurnRecord {
+        issuer: metadata.issuer,
+        course_id: metadata.course_id,
+        burned_at_ledger: 4242,
+    };
+    ctx.assert_last_event(
+        (symbol_short!("burned"), owner.clone(), certificate_id).into_val(&ctx.env),
+        record.clone().into_val(&ctx.env),
+    );
+    assert_eq!(ctx.client.burn_record(&certificate_id), Some(record));
+
+    assert_eq!(ctx.client.balance(&owner), 1);
+    assert_eq!(
+        ctx.client.tokens_of_owner(&owner, &0, &10),
+        vec![&ctx.env, first_id]
+    );
+    assert!(ctx.client.try_owner_of(&certificate_id).is_err());
+    assert!(ctx
+        .client
+        .try_certificate_metadata(&certificate_id)
+        .is_err());
+
+    // verifiers can tell a burned certificate from one that never existed
+    let result = ctx.client.verify_certificate(&certificate_id);
+    assert!(!result.valid);
+    assert_eq!(result.reasons, vec![&ctx.env, VerificationFailure::Burned]);
+    assert!(ctx.client.try_verify_certificate(&99).is_err());
+
+    ctx.client.bump_certificate(&certificate_id);
+}
+
+#[test]
+#[should_panic(expected = "#114")]
+fn test_burn_fails_if_not_owner() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint();
+
+    ctx.client
+        .burn(&Address::generate(&ctx.env), &certificate_id);
+}
+
+#[test]
+#[should_panic(expected = "#103")]
+fn test_burn_twice() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, _) = ctx.mint();
+
+    ctx.client.burn(&metadata.recipient, &certificate_id);
+    ctx.client.burn(&metadata.recipient, &certificate_id);
+}
+
+#[test]
+fn test_burn_soulbound_certificate_allows_new_completion() {
+    let ctx = TestContext::with_policy(TransferPolicy::Soulbound);
+    ctx.client.set_unique_completion(&true);
+
+    let recipient = Address::generate(&ctx.env);
+    let (certificate_id, metadata, _) = ctx.mint_to(&recipient, None, None);
+
+    ctx.client.burn(&recipient, &certificate_id);
+    assert!(!ctx.client.has_completed(&recipient, &metadata.course_id));
+
+    let (reissued_id, _, _) = ctx.mint_to(&recipient, None, None);
+    assert_eq!(ctx.client.owner_of(&reissued_id), recipient);
+}
+
+#[test]
+fn test_reissue_certificate() {
+    let ctx = TestContext::new();
+    ctx.client.set_unique_completion(&true);
+    let (first_id, metadata, sk) = ctx.mint();
+
+    let request = ctx.reissue_request(&sk, &metadata.issuer, first_id, 1);
+    let second_id = ctx.client.reissue_certificate(&first_id, &request);
+    ctx.assert_last_event(
+        (symbol_short!("reissued"), first_id).into_val(&ctx.env),
+        second_id.into_val(&ctx.env),
+    );
+
+    let detail = ctx.client.certificate_metadata(&first_id);
+    assert_eq!(detail.status, CertificateStatus::Superseded);
+    assert_eq!(
+        ctx.client.verify_certificate(&first_id).reasons,
+        vec![&ctx.env, VerificationFailure::Superseded]
+    );
+
+    let detail = ctx.client.certificate_metadata(&second_id);
+    assert_eq!(detail.owner, metadata.recipient);
+    assert_eq!(detail.metadata.metadata_uri, request.metadata_uri);
+    assert!(ctx.client.verify_certificate(&second_id).valid);
+
+    let request = ctx.reissue_request(&sk, &metadata.issuer, second_id, 2);
+    let third_id = ctx.client.reissue_certificate(&second_id, &request);
+
+    let history = vec![&ctx.env, first_id, second_id, third_id];
+    assert_eq!(ctx.client.certificate_history(&first_id), history);
+    assert_eq!(ctx.client.certificate_history(&second_id), history);
+    assert_eq!(ctx.client.certificate_history(&third_id), history);
+    assert_eq!(
+        ctx.client.certificate_history(&ctx.mint().0),
+        vec![&ctx.env, third_id + 1]
+    );
+}
+
+#[test]
+#[should_panic(expected = "#125")]
+fn test_reissue_certificate_fails_if_superseded() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, sk) = ctx.mint();
+
+    let request = ctx.reissue_request(&sk, &metadata.issuer, certificate_id, 1);
+    ctx.client.reissue_certificate(&certificate_id, &request);
+
+    let request = ctx.reissue_request(&sk, &metadata.issuer, certificate_id, 2);
+    ctx.client.reissue_certificate(&certificate_id, &request);
+}
+
+#[test]
+fn test_reissue_certificate_requires_reissue_signature() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, sk) = ctx.mint();
+
+    // a request signed for a plain mint cannot be replayed as a reissue
+    let mut request = ctx.reissue_request(&sk, &metadata.issuer, certificate_id, 1);
+    let mut plain = metadata.clone();
+    plain.metadata_uri = request.metadata_uri.clone();
+    plain.nonce = 1;
+    request.signature = sign(&ctx.env, plain, sk).into();
+
+    assert_eq!(
+        ctx.client
+            .try_reissue_certificate(&certificate_id, &request),
+        Err(Ok(Error::InvalidSignature))
+    );
+    assert_eq!(
+        ctx.client.certificate_metadata(&certificate_id).status,
+        CertificateStatus::Active
+    );
+    assert_eq!(
+        ctx.client.certificate_history(&certificate_id),
+        vec![&ctx.env, certificate_id]
+    );
+}
+
+#[test]
+#[should_panic(expected = "#102")]
+fn test_reissue_certificate_fails_for_other_issuer() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint();
+    let (other_sk, other_issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&other_issuer);
+
+    let request = ctx.reissue_request(&other_sk, &other_issuer, certificate_id, 0);
+    ctx.client.reissue_certificate(&certificate_id, &request);
+}
+
+#[test]
+#[should_panic(expected = "#109")]
+fn test_reissue_certificate_fails_for_other_recipient() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, sk) = ctx.mint();
+
+    let mut request = ctx.reissue_request(&sk, &metadata.issuer, certificate_id, 1);
+    request.recipient = Address::generate(&ctx.env);
+    ctx.client.reissue_certificate(&certificate_id, &request);
+}
+
+/// Signs a mint request, including its claims, with a secp256k1 key
+fn sign_mint_request(env: &Env, request: &MintRequest, sk: &SigningKey) -> Bytes {
+    let message = build_certificate_message(
+        env,
+        &request.recipient,
+        &request.course_id,
+        &request.metadata_uri,
+        &request.metadata_hash,
+        &request.issued_date,
+        &request.valid_from,
+        &request.expires_at,
+        &request.nonce,
+        &request.claims,
+    );
+
+    sign_message(env, &message, sk.clone()).into()
+}
+
+#[test]
+fn test_mint_certificate_with_claims() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let claims = Map::from_array(
+        &ctx.env,
+        [
+            (symbol_short!("grade"), ClaimValue::Int(87)),
+            (symbol_short!("credits"), ClaimValue::Int(6)),
+            (
+                symbol_short!("skill"),
+                ClaimValue::String(String::from_str(&ctx.env, "solidity")),
+            ),
+            (symbol_short!("honors"), ClaimValue::Bool(true)),
+        ],
+    );
+    let mut request = ctx.mint_request(&sk, &issuer, 0);
+    request.claims = claims.clone();
+    request.signature = sign_mint_request(&ctx.env, &request, &sk);
+
+    let certificate_id = ctx.mint_from_request(&request);
+
+    assert_eq!(
+        ctx.client.claim(&certificate_id, &symbol_short!("grade")),
+        Some(ClaimValue::Int(87))
+    );
+    assert_eq!(
+        ctx.client.claim(&certificate_id, &symbol_short!("gpa")),
+        None
+    );
+    assert_eq!(
+        ctx.client.certificate_metadata(&certificate_id).claims,
+        claims
+    );
+    assert!(ctx.client.verify_certificate(&certificate_id).valid);
+
+    // certificates without claims store none
+    let (other_id, _, _) = ctx.mint();
+    assert!(ctx.client.certificate_metadata(&other_id).claims.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "#105")]
+fn test_mint_certificate_claims_not_signed() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let mut request = ctx.mint_request(&sk, &issuer, 0);
+    request.claims = Map::from_array(&ctx.env, [(symbol_short!("grade"), ClaimValue::Int(100))]);
+
+    ctx.mint_from_request(&request);
+}
+
+#[test]
+#[should_panic(expected = "#109")]
+fn test_mint_certificate_too_many_claims() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let mut request = ctx.mint_request(&sk, &issuer, 0);
+    for index in 0..=MAX_CLAIMS {
+        let key = Symbol::new(&ctx.env, &std::format!("claim_{index}"));
+        request.claims.set(key, ClaimValue::Int(index.into()));
+    }
+    request.signature = sign_mint_request(&ctx.env, &request, &sk);
+
+    ctx.mint_from_request(&request);
+}
+
+#[test]
+#[should_panic(expected = "#103")]
+fn test_claim_certificate_not_found() {
+    let ctx = TestContext::new();
+
+    ctx.client.claim(&7, &symbol_short!("grade"));
+}
+
+#[test]
+fn test_verify_metadata() {
+    let ctx = TestContext::new();
+    let (certificate_id, _, _) = ctx.mint();
+    let document = sample_document(&ctx.env);
+
+    assert!(ctx
+        .client
+        .verify_metadata(&certificate_id, &document, &HashAlgorithm::Sha256));
+    assert!(!ctx
+        .client
+        .verify_metadata(&certificate_id, &document, &HashAlgorithm::Keccak256));
+
+    let mut tampered = document.clone();
+    tampered.push_back(b' ');
+    assert!(!ctx
+        .client
+        .verify_metadata(&certificate_id, &tampered, &HashAlgorithm::Sha256));
+}
+
+#[test]
+fn test_verify_metadata_keccak256() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+    let document = sample_document(&ctx.env);
+
+    let mut request = ctx.mint_request(&sk, &issuer, 0);
+    request.metadata_hash = ctx.env.crypto().keccak256(&document).into();
+    request.signature = sign_mint_request(&ctx.env, &request, &sk);
+    let certificate_id = ctx.mint_from_request(&request);
+
+    assert!(ctx
+        .client
+        .verify_metadata(&certificate_id, &document, &HashAlgorithm::Keccak256));
+    assert!(!ctx
+        .client
+        .verify_metadata(&certificate_id, &document, &HashAlgorithm::Sha256));
+}
+
+#[test]
+fn test_mint_certificate_metadata_hash_signed() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let mut request = ctx.mint_request(&sk, &issuer, 0);
+    request.metadata_hash = ctx.env.crypto().sha256(&Bytes::new(&ctx.env)).into();
+
+    assert_eq!(
+        ctx.client.try_mint_certificate(&request),
+        Err(Ok(Error::InvalidSignature))
+    );
+}
+
+#[test]
+fn test_mint_certificate_requires_metadata_hash() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let mut request = ctx.mint_request(&sk, &issuer, 0);
+    request.metadata_hash = BytesN::from_array(&ctx.env, &[0; 32]);
+    request.signature = sign_mint_request(&ctx.env, &request, &sk);
+
+    assert_eq!(
+        ctx.client.try_mint_certificate(&request),
+        Err(Ok(Error::InvalidData))
+    );
+}
+
+#[test]
+#[should_panic(expected = "#103")]
+fn test_verify_metadata_certificate_not_found() {
+    let ctx = TestContext::new();
+
+    ctx.client
+        .verify_metadata(&7, &sample_document(&ctx.env), &HashAlgorithm::Sha256);
+}
+
+#[test]
+fn test_mint_certificate_typed_message() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let mut request = ctx.mint_request(&sk, &issuer, 0);
+    request.claims = Map::from_array(&ctx.env, [(symbol_short!("grade"), ClaimValue::Int(92))]);
+    request.message_version = MessageVersion::Typed;
+
+    // a signature over the concatenated message is not valid for the typed one
+    assert_eq!(
+        ctx.client.try_mint_certificate(&request),
+        Err(Ok(Error::InvalidSignature))
+    );
+
+    let message = ctx.typed_message(&request, None);
+    assert_eq!(message.len(), 66);
+    assert_eq!(
+        message.slice(0..2),
+        Bytes::from_array(&ctx.env, &[0x19, 0x01])
+    );
+    request.signature = sign_message(&ctx.env, &message, sk).into();
+    let certificate_id = ctx.mint_from_request(&request);
+
+    let detail = ctx.client.certificate_metadata(&certificate_id);
+    assert_eq!(detail.message_version, MessageVersion::Typed);
+    assert!(ctx.client.verify_certificate(&certificate_id).valid);
+
+    let (certificate_id, _, _) = ctx.mint();
+    assert_eq!(
+        ctx.client
+            .certificate_metadata(&certificate_id)
+            .message_version,
+        MessageVersion::Concatenated
+    );
+}
+
+#[test]
+fn test_mint_certificate_typed_message_ed25519() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_ed25519_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let mut request = account_request(&ctx.env, &issuer);
+    request.message_version = MessageVersion::Typed;
+    request.signature = sign_ed25519(&ctx.env, &ctx.typed_message(&request, None), &sk);
+    let certificate_id = ctx.mint_from_request(&request);
+
+    assert!(ctx.client.verify_certificate(&certificate_id).valid);
+}
+
+#[test]
+fn test_typed_message_bound_to_contract() {
+    let ctx = TestContext::new();
+    let (sk, issuer) = generate_issuer(&ctx.env);
+    ctx.add_issuer(&issuer);
+
+    let other_address = ctx.env.register(
+        CertificateNFT,
+        (
+            String::from_str(&ctx.env, "Traxodus Cerficates"),
+            String::from_str(&ctx.env, "TxCerts"),
+        ),
+    );
+    let other = CertificateNFTClient::new(&ctx.env, &other_address);
+    other.initialize(&ctx.admin, &TransferPolicy::Transferable);
+    other.add_issuer(&ctx.admin, &issuer, &issuer_profile(&ctx.env));
+
+    assert_eq!(
+        ctx.client.domain_separator(),
+        build_domain_separator(&ctx.env, &ctx.nft_address)
+    );
+    assert_ne!(ctx.client.domain_separator(), other.domain_separator());
+
+    // a typed signature cannot be replayed on another deployment
+    let mut request = ctx.mint_request(&sk, &issuer, 0);
+    request.message_version = MessageVersion::Typed;
+    request.signature = sign_message(&ctx.env, &ctx.typed_message(&request, None), sk).into();
+
+    assert_eq!(
+        other.try_mint_certificate(&request),
+        Err(Ok(Error::InvalidSignature))
+    );
+    ctx.mint_from_request(&request);
+}
+
+#[test]
+fn test_typed_message_delimits_fields() {
+    let ctx = TestContext::new();
+    let domain_separator = ctx.client.domain_separator();
+    let claims = Map::new(&ctx.env);
+
+    let first = CertificateMetadata {
+        metadata_uri: String::from_str(&ctx.env, "ipfs://a1"),
+        issued_date: 234,
+        ..ctx.mint().1
+    };
+    let second = CertificateMetadata {
+        metadata_uri: String::from_str(&ctx.env, "ipfs://a"),
+        issued_date: 1234,
+        ..first.clone()
+    };
+
+    // shifting bytes between adjacent fields leaves the concatenated message unchanged
+    assert_eq!(
+        build_metadata_message(&ctx.env, &first, &claims),
+        build_metadata_message(&ctx.env, &second, &claims)
+    );
+    assert_ne!(
+        build_typed_certificate_message(&ctx.env, &domain_separator, &first, &claims, None),
+        build_typed_certificate_message(&ctx.env, &domain_separator, &second, &claims, None)
+    );
+}
+
+#[test]
+fn test_reissue_certificate_typed_message() {
+    let ctx = TestContext::new();
+    let (certificate_id, metadata, sk) = ctx.mint();
+
+    let mut request = ctx.reissue_request(&sk, &metadata.issuer, certificate_id, 1);
+    request.message_version = MessageVersion::Typed;
+
+    // the typed message of a plain mint cannot be replayed as a reissue
+    request.signature =
+        sign_message(&ctx.env, &ctx.typed_message(&request, None), sk.clone()).into();
+    assert!(ctx
+        .client
+        .try_reissue_certificate(&certificate_id, &request)
+        .is_err());
+
+    request.signature = sign_message(
+        &ctx.env,
+        &ctx.typed_message(&request, Some(certificate_id)),
+        sk,
+    )
+    .into();
+    let next_id = ctx.client.reissue_certificate(&certificate_id, &request);
+
+    assert!(ctx.client.verify_certificate(&next_id).valid);
+    assert_eq!(
+        ctx.client.certificate_history(&next_id),
+        vec![&ctx.env, certificate_id, next_id]
+    );
 }
@@ -1,11 +1,124 @@
-use soroban_sdk::{contracttype, symbol_short, Address, BytesN, String, Symbol};
+use soroban_sdk::{contracttype, symbol_short, Address, Bytes, BytesN, Map, String, Symbol, Vec};
 
 pub const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
 pub const COUNTER_KEY: Symbol = symbol_short!("COUNTER");
 pub const ISSUERS_KEY: Symbol = symbol_short!("ISSUERS");
+pub const ISSUER_COUNTER_KEY: Symbol = symbol_short!("ISSCOUNT");
 pub const PENDING_ADMIN: Symbol = symbol_short!("PENDING");
 pub const NAME_KEY: Symbol = symbol_short!("NAME");
 pub const SYMBOL_KEY: Symbol = symbol_short!("SYMBOL");
+pub const TTL_KEY: Symbol = symbol_short!("TTL");
+pub const POLICY_KEY: Symbol = symbol_short!("POLICY");
+pub const RECOVERY_DELAY_KEY: Symbol = symbol_short!("RECDELAY");
+pub const UNIQUE_COMPLETION_KEY: Symbol = symbol_short!("UNIQUE");
+pub const PAUSE_KEY: Symbol = symbol_short!("PAUSE");
+pub const VERSION_KEY: Symbol = symbol_short!("VERSION");
+pub const MIGRATION_KEY: Symbol = symbol_short!("MIGRATE");
+
+/// Version of the storage layout written by this code. Version 1 is the original
+/// layout, which kept certificates in instance storage.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Maximum number of claims a certificate can carry
+pub const MAX_CLAIMS: u32 = 20;
+
+/// Maximum number of certificates moved by a single `migrate` call
+pub const MIGRATION_BATCH_SIZE: u32 = 20;
+
+/// Maximum number of entries returned by a single page query
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Maximum number of requests accepted by `batch_mint_certificates`.
+/// Sized so that a full batch of the compiled contract stays within the network's
+/// per-transaction CPU, memory and written-entry limits, see
+/// `test_batch_mint_certificates_budget`. Each certificate writes up to 12 entries.
+pub const MAX_BATCH_SIZE: u32 = 4;
+
+pub const DAY_IN_LEDGERS: u32 = 17280;
+/// Default number of ledgers certificate entries are kept alive for when bumped
+pub const CERTIFICATE_BUMP_AMOUNT: u32 = 120 * DAY_IN_LEDGERS;
+/// Default remaining TTL below which certificate entries are bumped
+pub const CERTIFICATE_LIFETIME_THRESHOLD: u32 = CERTIFICATE_BUMP_AMOUNT - 30 * DAY_IN_LEDGERS;
+
+/// TTL thresholds applied to certificate entries in persistent storage
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TtlConfig {
+    /// Entries are only bumped once their TTL drops below this many ledgers
+    pub threshold: u32,
+    /// Number of ledgers an entry's TTL is extended to when bumped
+    pub extend_to: u32,
+}
+
+/// An entity trusted to issue certificates. Key issuers are tagged with their
+/// key type, which selects how their signatures are verified.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Issuer {
+    /// Uncompressed secp256k1 public key signing the keccak256 hash of the message
+    Secp256k1(BytesN<65>),
+    /// Stellar account or contract authorizing mints with `require_auth`
+    Account(Address),
+    /// Ed25519 public key, e.g. a Stellar-native key, signing the message itself
+    Ed25519(BytesN<32>),
+    /// Uncompressed secp256r1 public key, e.g. a passkey, signing the sha256 hash of the message
+    Secp256r1(BytesN<65>),
+}
+
+/// Certificate metadata as stored by schema version 1
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LegacyCertificateMetadata {
+    pub course_id: String,
+    pub issued_date: u64,
+    pub issuer: BytesN<65>,
+    pub metadata_uri: String,
+    pub signature: BytesN<65>,
+    pub recipient: Address,
+}
+
+/// Public profile and minting limits of a registered issuer
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuerProfile {
+    /// Display name of the issuing institution
+    pub name: String,
+    pub website: String,
+    /// URI of off-chain issuer metadata such as the logo
+    pub metadata_uri: String,
+    /// `course_id` prefixes the issuer may mint for, any course when empty
+    pub course_scopes: Vec<String>,
+    /// Maximum number of mints per `mint_period`, unlimited when `None`
+    pub max_mints: Option<u32>,
+    /// Length of the quota period in seconds, `max_mints` is a lifetime cap when 0
+    pub mint_period: u64,
+    /// Inactive issuers cannot mint, their certificates stay valid
+    pub active: bool,
+}
+
+/// A signing key of an issuer identity and the period in which it was the
+/// identity's active key. `valid_until` is exclusive and unset for the current key.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuerKey {
+    pub key: Issuer,
+    pub valid_from: u64,
+    pub valid_until: Option<u64>,
+}
+
+impl IssuerKey {
+    pub fn is_active_at(&self, timestamp: u64) -> bool {
+        self.valid_from <= timestamp && self.valid_until.is_none_or(|until| timestamp < until)
+    }
+}
+
+/// Mints counted against an issuer's quota in the current period
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MintWindow {
+    pub started_at: u64,
+    pub minted: u32,
+}
 
 /// Struct representing the metadata of a certificate NFT
 #[contracttype]
@@ -15,21 +128,259 @@ pub struct CertificateMetadata {
     pub course_id: String,
     /// Completion date in UNIX timestamp format
     pub issued_date: u64,
+    /// UNIX timestamp from which the certificate is valid, if restricted
+    pub valid_from: Option<u64>,
+    /// UNIX timestamp after which the certificate is no longer valid, if any
+    pub expires_at: Option<u64>,
+    /// Issuer-chosen nonce, unique per issuer, protecting against replayed signatures
+    pub nonce: u64,
     /// The issuer
-    pub issuer: BytesN<65>,
+    pub issuer: Issuer,
     /// URI pointing to full certificate metadata (e.g., IPFS URL)
     pub metadata_uri: String,
-    /// 65-byte ECDSA signature (r + s + v) of the hashed message
-    pub signature: BytesN<65>,
+    /// Hash of the document at `metadata_uri`, all zeros for certificates
+    /// migrated from before the commitment existed
+    pub metadata_hash: BytesN<32>,
+    /// Issuer signature of the message: 65-byte ECDSA signature (r + s + v) for
+    /// secp256k1 issuers, 64 bytes for ed25519 and secp256r1 (r + s) issuers,
+    /// empty for account issuers
+    pub signature: Bytes,
     /// Address of recipient
     pub recipient: Address,
 }
 
+/// Value of a typed certificate claim such as a grade, skill or credit hours
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClaimValue {
+    String(String),
+    Int(i128),
+    Bool(bool),
+    Bytes(Bytes),
+}
+
+/// Format of the message an issuer signs to mint a certificate
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageVersion {
+    /// Length-prefixed concatenation of the fields, see `build_certificate_message`
+    Concatenated,
+    /// Domain-separated hash of the typed fields, see `build_typed_certificate_message`
+    Typed,
+}
+
+/// Hash function used to check a metadata document against its commitment
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha256,
+    Keccak256,
+}
+
+/// Issuer-signed request to mint a single certificate
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MintRequest {
+    pub recipient: Address,
+    pub course_id: String,
+    pub metadata_uri: String,
+    pub metadata_hash: BytesN<32>,
+    pub issuer: Issuer,
+    pub issued_date: u64,
+    pub valid_from: Option<u64>,
+    pub expires_at: Option<u64>,
+    pub nonce: u64,
+    pub claims: Map<Symbol, ClaimValue>,
+    pub message_version: MessageVersion,
+    pub signature: Bytes,
+}
+
+/// Outcome of a single request in `batch_mint_certificates`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MintResult {
+    /// ID of the minted certificate
+    Minted(u32),
+    /// Code of the `Error` the request failed with
+    Failed(u32),
+}
+
+impl CertificateMetadata {
+    /// Returns true if `timestamp` falls within the certificate's validity window
+    pub fn is_valid_at(&self, timestamp: u64) -> bool {
+        self.valid_from.is_none_or(|from| timestamp >= from)
+            && self.expires_at.is_none_or(|expiry| timestamp <= expiry)
+    }
+}
+
+/// Operator roles the admin can grant. The admin implicitly holds every role.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// Registers, updates and removes issuers
+    IssuerManager,
+    /// Revokes certificates
+    Revoker,
+    /// Pauses and unpauses the contract
+    Pauser,
+    /// Upgrades the contract code
+    Upgrader,
+}
+
+/// Entry points that can be paused individually
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// `mint_certificate` and `batch_mint_certificates`
+    Mint,
+    /// Every change of certificate ownership, including burning
+    Transfer,
+    /// Certificate revocation
+    Revoke,
+}
+
+/// Pause state of the contract
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PauseState {
+    /// Pauses every operation
+    pub global: bool,
+    /// Operations paused individually
+    pub operations: Vec<Operation>,
+}
+
+/// Party that revoked a certificate
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Revoker {
+    /// The admin or an account holding the `Revoker` role
+    Operator(Address),
+    /// The issuer of the certificate
+    Issuer(Issuer),
+}
+
+/// Revocation record stored for every revoked certificate
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevocationRecord {
+    /// Why the certificate was revoked
+    pub reason: String,
+    /// Ledger timestamp at which the certificate was revoked
+    pub revoked_at: u64,
+    /// Who revoked the certificate
+    pub revoked_by: Revoker,
+}
+
+/// Current status of a certificate
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CertificateStatus {
+    Active,
+    Revoked,
+    /// Replaced by a reissued certificate
+    Superseded,
+    Expired,
+    NotYetValid,
+}
+
+/// Reason a certificate failed verification
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationFailure {
+    Revoked,
+    Expired,
+    NotYetValid,
+    /// The issuer was removed from the registry
+    IssuerNotTrusted,
+    /// The signing key was not its issuer's active key on the issue date
+    IssuerKeyInactive,
+    /// The stored signature does not cover the stored metadata
+    InvalidSignature,
+    /// A signature was presented for an owner without a Stellar account key
+    InvalidHolderProof,
+    /// The holder burned the certificate, no other check was run
+    Burned,
+    /// The certificate was replaced by a reissued one
+    Superseded,
+}
+
+/// Outcome of `verify_certificate`. A certificate is valid if no check failed.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationResult {
+    pub valid: bool,
+    pub reasons: Vec<VerificationFailure>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[contracttype]
 pub struct CertificateDetail {
     pub owner: Address,
     pub metadata: CertificateMetadata,
+    pub claims: Map<Symbol, ClaimValue>,
+    pub message_version: MessageVersion,
+    pub status: CertificateStatus,
+}
+
+/// Rules governing who may move certificates between owners
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferPolicy {
+    /// Certificates can never change owner
+    Soulbound,
+    /// Only the admin can move certificates, e.g. for wallet recovery
+    AdminOnly,
+    /// Owners and their approved spenders can transfer freely
+    Transferable,
+}
+
+/// Tombstone left behind when a holder burns a certificate
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BurnRecord {
+    pub issuer: Issuer,
+    pub course_id: String,
+    /// Ledger sequence in which the certificate was burned
+    pub burned_at_ledger: u32,
+}
+
+/// Wallet recovery proposed by the admin, awaiting acceptance by the new owner
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecoveryRequest {
+    /// Address the certificates will be moved to
+    pub new_owner: Address,
+    /// Certificates to move
+    pub certificate_ids: Vec<u32>,
+    /// Ledger timestamp before which the recovery cannot be accepted
+    pub unlock_at: u64,
+}
+
+/// Single-token approval granted by a certificate owner
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApprovalData {
+    pub approved: Address,
+    pub live_until_ledger: u32,
+}
+
+/// Payload of the `minted` event
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MintedEvent {
+    pub issuer_id: u32,
+    pub issuer: Issuer,
+    pub course_id: String,
+    /// SHA-256 of the UTF-8 bytes of the metadata URI
+    pub metadata_uri_hash: BytesN<32>,
+}
+
+/// Payload of the `issuer_key_rotated` event
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyRotatedEvent {
+    pub old_key: Issuer,
+    pub new_key: Issuer,
 }
 
 #[contracttype]
@@ -39,4 +390,25 @@ pub enum DataKey {
     CertificateMetadata(u32),
     CertificateUri(u32),
     CerticateRegister(Address),
+    CertificateRevocation(u32),
+    IssuerNonce(Issuer, u64),
+    Approval(u32),
+    ApprovalForAll(Address, Address),
+    PendingRecovery(Address),
+    OwnedCertificate(Address, u32),
+    OwnedCertificateIndex(u32),
+    CourseCertificate(String, u32),
+    CourseCertificateCount(String),
+    CourseCompletion(Address, String, u32),
+    CourseCompletionCount(Address, String),
+    IssuerIdentity(Issuer),
+    IssuerKeys(u32),
+    IssuerProfile(u32),
+    IssuerMintWindow(u32),
+    LegacyCertificate(u32),
+    CertificateBurn(u32),
+    PreviousCertificate(u32),
+    NextCertificate(u32),
+    CertificateClaims(u32),
+    CertificateMessageVersion(u32),
 }
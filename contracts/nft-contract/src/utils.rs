@@ -1,25 +1,47 @@
-use soroban_sdk::{vec, Address, Bytes, BytesN, Env, String, Vec};
+use crate::types::{CertificateMetadata, ClaimValue, HashAlgorithm, Issuer};
+use soroban_sdk::{vec, xdr::ToXdr, Address, Bytes, BytesN, Env, Map, String, Symbol, Vec};
 
-/// Builds a deterministic message from certificate fields to be signed by the issuer
+/// Builds a deterministic message from certificate fields to be signed by the issuer.
+/// Claims are appended as the XDR encoding of the map, keys in ascending order, and
+/// omitted entirely when there are none.
 pub fn build_certificate_message(
     env: &Env,
     recipient: &Address,
     course_id: &String,
     metadata_uri: &String,
+    metadata_hash: &BytesN<32>,
     issued_date: &u64,
+    valid_from: &Option<u64>,
+    expires_at: &Option<u64>,
+    nonce: &u64,
+    claims: &Map<Symbol, ClaimValue>,
 ) -> Bytes {
-    let prefix = String::from_str(&env, "\x19Tradoxus Signed Message:\n");
+    let prefix = String::from_str(env, "\x19Tradoxus Signed Message:\n");
 
     let prefix_bytes = string_to_bytes(env, prefix);
     let course_id_bytes = string_to_bytes(env, course_id.clone());
     let recipient_bytes = string_to_bytes(env, recipient.to_string());
     let metadata_uri_bytes = string_to_bytes(env, metadata_uri.clone());
     let completion_date_bytes = number_to_string_bytes(env, *issued_date);
+    // unset validity bounds are encoded as 0
+    let valid_from_bytes = number_to_string_bytes(env, valid_from.unwrap_or(0));
+    let expires_at_bytes = number_to_string_bytes(env, expires_at.unwrap_or(0));
+    let nonce_bytes = number_to_string_bytes(env, *nonce);
+    let metadata_hash_bytes: Bytes = metadata_hash.clone().into();
+    let claims_bytes = match claims.is_empty() {
+        true => Bytes::new(env),
+        false => claims.clone().to_xdr(env),
+    };
 
     let message_len = course_id_bytes.len()
         + recipient_bytes.len()
         + metadata_uri_bytes.len()
-        + completion_date_bytes.len();
+        + completion_date_bytes.len()
+        + valid_from_bytes.len()
+        + expires_at_bytes.len()
+        + nonce_bytes.len()
+        + metadata_hash_bytes.len()
+        + claims_bytes.len();
 
     let len_bytes = number_to_string_bytes(env, message_len.into());
 
@@ -33,12 +55,364 @@ pub fn build_certificate_message(
             recipient_bytes,
             metadata_uri_bytes,
             completion_date_bytes,
+            valid_from_bytes,
+            expires_at_bytes,
+            nonce_bytes,
+            metadata_hash_bytes,
+            claims_bytes,
         ],
     );
 
     message
 }
 
+/// Builds the message an issuer signs to revoke a certificate it issued on the
+/// certificate contract at `contract`. Certificate IDs start at 0 in every
+/// deployment, so the message is bound to the contract and network.
+pub fn build_revocation_message(
+    env: &Env,
+    contract: &Address,
+    certificate_id: &u32,
+    reason: &String,
+) -> Bytes {
+    let prefix = String::from_str(env, "\x19Tradoxus Signed Message:\n");
+
+    let prefix_bytes = string_to_bytes(env, prefix);
+    let action_bytes = string_to_bytes(env, String::from_str(env, "revoke"));
+    let deployment_bytes = deployment_bytes(env, contract);
+    let certificate_id_bytes = number_to_string_bytes(env, (*certificate_id).into());
+    let reason_bytes = string_to_bytes(env, reason.clone());
+
+    let message_len = action_bytes.len()
+        + deployment_bytes.len()
+        + certificate_id_bytes.len()
+        + reason_bytes.len();
+
+    let len_bytes = number_to_string_bytes(env, message_len.into());
+
+    concatenate_bytes(
+        env,
+        vec![
+            env,
+            prefix_bytes,
+            len_bytes,
+            action_bytes,
+            deployment_bytes,
+            certificate_id_bytes,
+            reason_bytes,
+        ],
+    )
+}
+
+// Identifies a deployment by the contract's strkey followed by the network id, the
+// sha256 of the network passphrase. Both have a fixed length.
+fn deployment_bytes(env: &Env, contract: &Address) -> Bytes {
+    let mut bytes = string_to_bytes(env, contract.to_string());
+    bytes.append(&env.ledger().network_id().into());
+
+    bytes
+}
+
+/// Builds the message an issuer's current key signs to hand over to `new_key` on the
+/// certificate contract at `contract`, bound to the contract and network like
+/// `build_revocation_message`
+pub fn build_rotation_message(
+    env: &Env,
+    contract: &Address,
+    issuer_id: &u32,
+    new_key: &Issuer,
+) -> Bytes {
+    let prefix = String::from_str(env, "\x19Tradoxus Signed Message:\n");
+
+    let prefix_bytes = string_to_bytes(env, prefix);
+    let action_bytes = string_to_bytes(env, String::from_str(env, "rotate"));
+    let deployment_bytes = deployment_bytes(env, contract);
+    let issuer_id_bytes = number_to_string_bytes(env, (*issuer_id).into());
+    let new_key_bytes = new_key.clone().to_xdr(env);
+
+    let message_len =
+        action_bytes.len() + deployment_bytes.len() + issuer_id_bytes.len() + new_key_bytes.len();
+
+    let len_bytes = number_to_string_bytes(env, message_len.into());
+
+    concatenate_bytes(
+        env,
+        vec![
+            env,
+            prefix_bytes,
+            len_bytes,
+            action_bytes,
+            deployment_bytes,
+            issuer_id_bytes,
+            new_key_bytes,
+        ],
+    )
+}
+
+/// Builds the message an issuer signs to reissue certificate `previous_id`, binding
+/// the successor's certificate message to the certificate it replaces
+pub fn build_reissue_message(env: &Env, previous_id: &u32, certificate_message: &Bytes) -> Bytes {
+    let prefix = String::from_str(env, "\x19Tradoxus Signed Message:\n");
+
+    let prefix_bytes = string_to_bytes(env, prefix);
+    let action_bytes = string_to_bytes(env, String::from_str(env, "reissue"));
+    let previous_id_bytes = number_to_string_bytes(env, (*previous_id).into());
+
+    let message_len = action_bytes.len() + previous_id_bytes.len() + certificate_message.len();
+
+    let len_bytes = number_to_string_bytes(env, message_len.into());
+
+    concatenate_bytes(
+        env,
+        vec![
+            env,
+            prefix_bytes,
+            len_bytes,
+            action_bytes,
+            previous_id_bytes,
+            certificate_message.clone(),
+        ],
+    )
+}
+
+/// Builds the message a certificate holder signs to prove control of the owning
+/// wallet to a verifier that chose `challenge`, bound to the certificate contract at
+/// `contract` and its network like `build_revocation_message`
+pub fn build_presentation_message(
+    env: &Env,
+    contract: &Address,
+    certificate_id: &u32,
+    challenge: &Bytes,
+) -> Bytes {
+    let prefix = String::from_str(env, "\x19Tradoxus Signed Message:\n");
+
+    let prefix_bytes = string_to_bytes(env, prefix);
+    let action_bytes = string_to_bytes(env, String::from_str(env, "present"));
+    let deployment_bytes = deployment_bytes(env, contract);
+    let certificate_id_bytes = number_to_string_bytes(env, (*certificate_id).into());
+
+    let message_len =
+        action_bytes.len() + deployment_bytes.len() + certificate_id_bytes.len() + challenge.len();
+
+    let len_bytes = number_to_string_bytes(env, message_len.into());
+
+    concatenate_bytes(
+        env,
+        vec![
+            env,
+            prefix_bytes,
+            len_bytes,
+            action_bytes,
+            deployment_bytes,
+            certificate_id_bytes,
+            challenge.clone(),
+        ],
+    )
+}
+
+/// Returns the ed25519 public key of a Stellar account address, or `None` for
+/// contract addresses
+pub fn account_public_key(env: &Env, address: &Address) -> Option<BytesN<32>> {
+    // ScVal::Address XDR: value type, address type (0 = account), key type, key
+    let xdr = address.clone().to_xdr(env);
+    if xdr.len() != 44 || xdr.get(7) != Some(0) {
+        return None;
+    }
+
+    BytesN::try_from(xdr.slice(12..)).ok()
+}
+
+/// Builds the message issuers signed before schema version 2, which did not cover
+/// the validity window or nonce. Used to verify migrated certificates.
+pub fn build_legacy_certificate_message(env: &Env, metadata: &CertificateMetadata) -> Bytes {
+    let prefix = String::from_str(env, "\x19Tradoxus Signed Message:\n");
+
+    let prefix_bytes = string_to_bytes(env, prefix);
+    let course_id_bytes = string_to_bytes(env, metadata.course_id.clone());
+    let recipient_bytes = string_to_bytes(env, metadata.recipient.to_string());
+    let metadata_uri_bytes = string_to_bytes(env, metadata.metadata_uri.clone());
+    let completion_date_bytes = number_to_string_bytes(env, metadata.issued_date);
+
+    let message_len = course_id_bytes.len()
+        + recipient_bytes.len()
+        + metadata_uri_bytes.len()
+        + completion_date_bytes.len();
+
+    let len_bytes = number_to_string_bytes(env, message_len.into());
+
+    concatenate_bytes(
+        env,
+        vec![
+            env,
+            prefix_bytes,
+            len_bytes,
+            course_id_bytes,
+            recipient_bytes,
+            metadata_uri_bytes,
+            completion_date_bytes,
+        ],
+    )
+}
+
+/// Builds the signed message of an already minted certificate
+pub fn build_metadata_message(
+    env: &Env,
+    metadata: &CertificateMetadata,
+    claims: &Map<Symbol, ClaimValue>,
+) -> Bytes {
+    build_certificate_message(
+        env,
+        &metadata.recipient,
+        &metadata.course_id,
+        &metadata.metadata_uri,
+        &metadata.metadata_hash,
+        &metadata.issued_date,
+        &metadata.valid_from,
+        &metadata.expires_at,
+        &metadata.nonce,
+        claims,
+    )
+}
+
+// Typed-data domain, modelled on EIP-712. Stellar addresses are encoded as their
+// strkey strings and the network is identified by the hash of its passphrase.
+const DOMAIN_TYPE: &str =
+    "Domain(string name,string version,bytes32 networkId,string verifyingContract)";
+const DOMAIN_NAME: &str = "Tradoxus Certificates";
+const DOMAIN_VERSION: &str = "1";
+
+const CERTIFICATE_TYPE: &str = "Certificate(string recipient,string courseId,string metadataUri,bytes32 metadataHash,uint64 issuedDate,uint64 validFrom,uint64 expiresAt,uint64 nonce,bytes claims)";
+const REISSUE_TYPE: &str = "Reissue(uint32 previousId,Certificate certificate)";
+
+/// Hashes the typed-data domain of the certificate contract at `contract`
+pub fn build_domain_separator(env: &Env, contract: &Address) -> BytesN<32> {
+    let network_id: Bytes = env.ledger().network_id().into();
+
+    let encoded = concatenate_bytes(
+        env,
+        vec![
+            env,
+            type_hash(env, &[DOMAIN_TYPE]),
+            encode_dynamic(env, &Bytes::from_slice(env, DOMAIN_NAME.as_bytes())),
+            encode_dynamic(env, &Bytes::from_slice(env, DOMAIN_VERSION.as_bytes())),
+            network_id,
+            encode_dynamic(env, &string_to_bytes(env, contract.to_string())),
+        ],
+    );
+
+    env.crypto().keccak256(&encoded).into()
+}
+
+/// Builds the typed-data message an issuer signs to mint a certificate:
+/// `0x19 0x01 || domain separator || struct hash`. Every field is encoded as a
+/// 32-byte word, so field boundaries are unambiguous. When reissuing, the
+/// certificate is wrapped in a `Reissue` struct bound to `previous_id`.
+pub fn build_typed_certificate_message(
+    env: &Env,
+    domain_separator: &BytesN<32>,
+    metadata: &CertificateMetadata,
+    claims: &Map<Symbol, ClaimValue>,
+    previous_id: Option<u32>,
+) -> Bytes {
+    let certificate = concatenate_bytes(
+        env,
+        vec![
+            env,
+            type_hash(env, &[CERTIFICATE_TYPE]),
+            encode_dynamic(env, &string_to_bytes(env, metadata.recipient.to_string())),
+            encode_dynamic(env, &string_to_bytes(env, metadata.course_id.clone())),
+            encode_dynamic(env, &string_to_bytes(env, metadata.metadata_uri.clone())),
+            metadata.metadata_hash.clone().into(),
+            encode_uint(env, metadata.issued_date),
+            // unset validity bounds are encoded as 0
+            encode_uint(env, metadata.valid_from.unwrap_or(0)),
+            encode_uint(env, metadata.expires_at.unwrap_or(0)),
+            encode_uint(env, metadata.nonce),
+            encode_dynamic(env, &claims.clone().to_xdr(env)),
+        ],
+    );
+    let mut struct_hash = encode_dynamic(env, &certificate);
+
+    if let Some(previous_id) = previous_id {
+        let reissue = concatenate_bytes(
+            env,
+            vec![
+                env,
+                type_hash(env, &[REISSUE_TYPE, CERTIFICATE_TYPE]),
+                encode_uint(env, previous_id.into()),
+                struct_hash,
+            ],
+        );
+        struct_hash = encode_dynamic(env, &reissue);
+    }
+
+    concatenate_bytes(
+        env,
+        vec![
+            env,
+            Bytes::from_array(env, &[0x19, 0x01]),
+            domain_separator.clone().into(),
+            struct_hash,
+        ],
+    )
+}
+
+// keccak256 of a type string, made of the primary type followed by the types it references
+fn type_hash(env: &Env, types: &[&str]) -> Bytes {
+    let mut encoded = Bytes::new(env);
+    for type_string in types {
+        encoded.extend_from_slice(type_string.as_bytes());
+    }
+
+    encode_dynamic(env, &encoded)
+}
+
+// strings, bytes and structs are encoded as the keccak256 hash of their contents
+fn encode_dynamic(env: &Env, value: &Bytes) -> Bytes {
+    env.crypto().keccak256(value).to_bytes().into()
+}
+
+// integers are encoded as 32-byte big-endian words
+fn encode_uint(env: &Env, value: u64) -> Bytes {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+
+    Bytes::from_array(env, &word)
+}
+
+/// Verifies an issuer's signature over a message according to the issuer's key type.
+/// Account issuers authenticate with `require_auth` instead and have no signature
+/// to verify. Returns false for a signature of the wrong length and for a secp256k1
+/// signature recovering another key. Any other mismatch cannot be reported: the host
+/// aborts the whole invocation when an ed25519 or secp256r1 signature does not match,
+/// or when no key can be recovered from a secp256k1 signature.
+pub fn verify_signature(env: &Env, issuer: &Issuer, signature: &Bytes, message: &Bytes) -> bool {
+    match issuer {
+        Issuer::Secp256k1(pub_key) => match BytesN::<65>::try_from(signature) {
+            Ok(signature) => verify_issuer_signature(env, pub_key, &signature, message),
+            Err(_) => false,
+        },
+        Issuer::Ed25519(pub_key) => match BytesN::<64>::try_from(signature) {
+            Ok(signature) => {
+                // host aborts on mismatch
+                env.crypto().ed25519_verify(pub_key, message, &signature);
+                true
+            }
+            Err(_) => false,
+        },
+        Issuer::Secp256r1(pub_key) => match BytesN::<64>::try_from(signature) {
+            Ok(signature) => {
+                let digest = env.crypto().sha256(message);
+                // host aborts on mismatch
+                env.crypto().secp256r1_verify(pub_key, &digest, &signature);
+                true
+            }
+            Err(_) => false,
+        },
+        Issuer::Account(_) => false,
+    }
+}
+
 /// Verifies a secp256k1 signature against a keccak256 hash of the message
 pub fn verify_issuer_signature(
     env: &Env,
@@ -68,7 +442,7 @@ pub fn verify_issuer_signature(
     {
         // To view this log run: `make test -- --no-capture`
         let signature = Signature::from_bytes(r_s.into()).expect("invalid r+s");
-        let recovery_ = RecoveryId::try_from(recovery_id as u8).unwrap();
+        let recovery_ = RecoveryId::try_from(recovery_id).unwrap();
         let recovered_key =
             VerifyingKey::recover_from_prehash(&hash.to_array(), &signature, recovery_).unwrap();
         let expected_key = bytesn65_to_verifying_key(pub_key);
@@ -104,7 +478,7 @@ pub fn string_to_bytes(env: &Env, str1: String) -> Bytes {
     let mut buffer: [u8; 300000] = [0; 300000];
 
     str1.copy_into_slice(&mut buffer[..str_len]);
-    Bytes::from_slice(&env, &buffer[0..str_len])
+    Bytes::from_slice(env, &buffer[0..str_len])
 }
 
 // represent a number as a series of bytes which stand for the ASCII code for each number
@@ -138,7 +512,32 @@ pub fn number_to_string_bytes(env: &Env, number: u64) -> Bytes {
     let reversed_buffer = &mut buffer[0..len];
     reversed_buffer.reverse();
 
-    Bytes::from_slice(&env, reversed_buffer)
+    Bytes::from_slice(env, reversed_buffer)
+}
+
+// check whether a string starts with the given prefix
+pub fn has_prefix(env: &Env, value: &String, prefix: &String) -> bool {
+    if prefix.len() > value.len() {
+        return false;
+    }
+
+    let value_bytes = string_to_bytes(env, value.clone());
+    let prefix_bytes = string_to_bytes(env, prefix.clone());
+
+    value_bytes.slice(0..prefix.len()) == prefix_bytes
+}
+
+// check whether a hash is all zeros, i.e. no commitment was made
+pub fn is_zero_hash(hash: &BytesN<32>) -> bool {
+    hash.to_array() == [0u8; 32]
+}
+
+/// Hashes a document with the given algorithm
+pub fn hash_document(env: &Env, content: &Bytes, algorithm: &HashAlgorithm) -> BytesN<32> {
+    match algorithm {
+        HashAlgorithm::Sha256 => env.crypto().sha256(content).into(),
+        HashAlgorithm::Keccak256 => env.crypto().keccak256(content).into(),
+    }
 }
 
 // concatenate multiple bytes === abi.encodePacked implementation
@@ -152,11 +551,9 @@ pub fn concatenate_bytes(env: &Env, strings: Vec<Bytes>) -> Bytes {
         }
     }
 
-    return concatenated_bytes;
+    concatenated_bytes
 }
 
-#[cfg(test)]
-use crate::types::CertificateMetadata;
 #[cfg(test)]
 use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
 #[cfg(test)]
@@ -177,15 +574,14 @@ pub fn gen_random_bytes<const N: usize>(env: &Env) -> BytesN<N> {
 
 #[cfg(test)]
 pub fn sign(env: &Env, metadata: CertificateMetadata, signing_key: SigningKey) -> BytesN<65> {
-    let message = build_certificate_message(
-        &env,
-        &metadata.recipient,
-        &metadata.course_id,
-        &metadata.metadata_uri,
-        &metadata.issued_date,
-    );
+    let message = build_metadata_message(env, &metadata, &Map::new(env));
 
-    let hash = env.crypto().keccak256(&message);
+    sign_message(env, &message, signing_key)
+}
+
+#[cfg(test)]
+pub fn sign_message(env: &Env, message: &Bytes, signing_key: SigningKey) -> BytesN<65> {
+    let hash = env.crypto().keccak256(message);
 
     let (sig, recovery_id) = signing_key
         .sign_prehash_recoverable(&hash.to_array())
@@ -195,7 +591,7 @@ pub fn sign(env: &Env, metadata: CertificateMetadata, signing_key: SigningKey) -
     sig_bytes[..64].copy_from_slice(&sig.to_vec()); // r + s
     sig_bytes[64] = recovery_id.into(); // v
 
-    BytesN::from_array(&env, &sig_bytes)
+    BytesN::from_array(env, &sig_bytes)
 }
 
 #[cfg(test)]
@@ -215,6 +611,71 @@ pub fn generate_keypair(env: &Env) -> (SigningKey, VerifyingKey, BytesN<65>) {
     (signing_key, verifying_key, public_key)
 }
 
+#[cfg(test)]
+pub fn generate_issuer(env: &Env) -> (SigningKey, Issuer) {
+    let (signing_key, _, public_key) = generate_keypair(env);
+    (signing_key, Issuer::Secp256k1(public_key))
+}
+
+#[cfg(test)]
+pub fn generate_ed25519_issuer(env: &Env) -> (ed25519_dalek::SigningKey, Issuer) {
+    let signing_key = ed25519_dalek::SigningKey::generate(&mut OsRng);
+    let public_key = BytesN::from_array(env, &signing_key.verifying_key().to_bytes());
+
+    (signing_key, Issuer::Ed25519(public_key))
+}
+
+/// Generates a Stellar account address with its ed25519 signing key
+#[cfg(test)]
+pub fn generate_account(env: &Env) -> (ed25519_dalek::SigningKey, Address) {
+    use soroban_sdk::xdr::FromXdr;
+
+    let signing_key = ed25519_dalek::SigningKey::generate(&mut OsRng);
+
+    let mut xdr = Bytes::from_array(env, &[0, 0, 0, 18, 0, 0, 0, 0, 0, 0, 0, 0]);
+    xdr.extend_from_array(&signing_key.verifying_key().to_bytes());
+
+    (signing_key, Address::from_xdr(env, &xdr).unwrap())
+}
+
+#[cfg(test)]
+pub fn sign_ed25519(env: &Env, message: &Bytes, signing_key: &ed25519_dalek::SigningKey) -> Bytes {
+    use ed25519_dalek::Signer;
+
+    let mut buf = [0u8; 1024];
+    let buf = &mut buf[..message.len() as usize];
+    message.copy_into_slice(buf);
+
+    let signature = signing_key.sign(buf);
+    Bytes::from_array(env, &signature.to_bytes())
+}
+
+#[cfg(test)]
+pub fn generate_secp256r1_issuer(env: &Env) -> (p256::ecdsa::SigningKey, Issuer) {
+    let signing_key = p256::ecdsa::SigningKey::random(&mut OsRng);
+    let encoded = signing_key.verifying_key().to_encoded_point(false);
+
+    let mut pubkey_arr = [0u8; 65];
+    pubkey_arr.copy_from_slice(encoded.as_bytes());
+
+    (
+        signing_key,
+        Issuer::Secp256r1(BytesN::from_array(env, &pubkey_arr)),
+    )
+}
+
+#[cfg(test)]
+pub fn sign_secp256r1(env: &Env, message: &Bytes, signing_key: &p256::ecdsa::SigningKey) -> Bytes {
+    use p256::ecdsa::signature::hazmat::PrehashSigner;
+
+    let digest = env.crypto().sha256(message);
+    let signature: p256::ecdsa::Signature = signing_key.sign_prehash(&digest.to_array()).unwrap();
+    // the host only accepts low-S signatures
+    let signature = signature.normalize_s().unwrap_or(signature);
+
+    Bytes::from_slice(env, &signature.to_bytes())
+}
+
 #[cfg(test)]
 fn bytesn65_to_verifying_key(bytes: &BytesN<65>) -> VerifyingKey {
     let encoded = k256::EncodedPoint::from_bytes(bytes.to_array()).expect("invalid pubkey bytes");